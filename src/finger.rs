@@ -0,0 +1,179 @@
+//! A module with all the finger protocol functionality.
+
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::net::{self, ConnectError};
+
+use thiserror::Error;
+
+/// The port finger servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 79;
+
+/// Represents the different error types this module returns
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to parse URL: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("The given URL didn't use the finger scheme: {0}")]
+    UrlScheme(String),
+    #[error("The given URL didn't have a host: {0}")]
+    UrlNoHost(String),
+    #[error("The URL couldn't be resolved to an address: {0}")]
+    UrlNoAddress(String),
+    #[error("Couldn't connect to address {1}, TCP connection error: {0}")]
+    TCPConnect(std::io::Error, String),
+    #[error("Stream IO failure, {0}: {1}")]
+    StreamIO(&'static str, std::io::Error),
+}
+
+/// Represents a client which will make finger requests.
+pub struct Client {
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a client that can be used to make finger requests
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::finger::Client;
+    ///
+    /// let client = Client::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Client {
+        Self::with_timeout(None)
+    }
+
+    /// Creates a client that can be used to make finger requests with a timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::finger::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::with_timeout(Some(Duration::new(5, 0)));
+    /// ```
+    #[must_use]
+    pub fn with_timeout(timeout: Option<Duration>) -> Client {
+        Client { timeout }
+    }
+
+    /// Sets the timeout for the client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::finger::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new();
+    /// // A timeout of 5 seconds
+    /// client.set_timeout(Some(Duration::from_secs(5)));
+    /// // No timeout
+    /// client.set_timeout(None);
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Parses a `finger://host[:port]/user` URL into the host string to connect to and the user
+    /// to query. The user is empty if the URL has no path, which asks the server for a list of
+    /// its users.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::finger::Client;
+    ///
+    /// let (host, user) = Client::parse_url("finger://example.org/alice").unwrap();
+    /// assert_eq!(host, "example.org:79");
+    /// assert_eq!(user, "alice");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`enum@Error`] if the URL is malformed, doesn't use the `finger` scheme, or
+    /// has no host.
+    pub fn parse_url(url: &str) -> Result<(String, String), Error> {
+        let url_parsed = url::Url::parse(url)?;
+        if url_parsed.scheme() != "finger" {
+            return Err(Error::UrlScheme(url.to_string()));
+        }
+        let host_str = url_parsed
+            .host_str()
+            .ok_or_else(|| Error::UrlNoHost(url.to_string()))?;
+        let host = net::ascii_host(host_str)?;
+        let port = url_parsed.port().unwrap_or(DEFAULT_PORT);
+        let user = url_parsed.path().trim_start_matches('/').to_string();
+
+        Ok((format!("{}:{}", host, port), user))
+    }
+
+    /// Queries `host` for information about `user`.
+    ///
+    /// `host` may include a port in the form `<host>:<port>`, or `[<address>]:<port>` for an IPv6
+    /// address, otherwise the default finger port is used. An empty `user` asks the server for a
+    /// list of its users.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::finger::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request("example.org", "alice");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`enum@Error`] if there was a problem communicating with the server.
+    pub fn request(&self, host: &str, user: &str) -> Result<String, Error> {
+        let host = net::with_port(host, DEFAULT_PORT);
+
+        let mut stream = net::connect(&host, self.timeout).map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(host.clone()),
+            ConnectError::Io(e) => Error::TCPConnect(e, host.clone()),
+        })?;
+
+        stream
+            .write_all(format!("{}\r\n", user).as_bytes())
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+
+        Ok(String::from_utf8_lossy(&response).to_string())
+    }
+
+    /// Queries the host and user described by a `finger://` URL.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::finger::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request_url("finger://example.org/alice");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`enum@Error`] if there was a problem with parsing the url or communicating
+    /// with the server.
+    pub fn request_url(&self, url: &str) -> Result<String, Error> {
+        let (host, user) = Self::parse_url(url)?;
+        self.request(&host, &user)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
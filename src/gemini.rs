@@ -1,38 +1,138 @@
 //! A module with all the gemini protocol functionality.
 
 /// Make gemini requests and parse their responses.
+#[cfg(feature = "client")]
 mod client;
+/// Fetch the emoji capsules use as their icon.
+#[cfg(feature = "client")]
+pub mod favicon;
 /// Represent and parse gemtext documents.
 pub mod gemtext;
 /// Represent a gemini response's header.
 pub mod header;
+/// Remember the certificates hosts presented, trusting them on first use.
+#[cfg(feature = "client")]
+pub mod known_hosts;
+/// Decide which redirects a client follows.
+#[cfg(feature = "client")]
+mod redirect;
 /// Represent a gemini response.
+#[cfg(feature = "client")]
 mod response;
+/// Follow the rules capsules set for crawlers.
+#[cfg(feature = "client")]
+pub mod robots;
+/// Serve canned responses over TLS for testing.
+#[cfg(all(feature = "rustls", any(test, feature = "test-util")))]
+pub mod test_server;
+/// Represent titan uploads.
+#[cfg(feature = "client")]
+mod titan;
+/// Secure connections with a choice of TLS backend.
+#[cfg(feature = "client")]
+pub mod tls;
+/// Abstract over how connections to servers are opened.
+#[cfg(feature = "client")]
+pub mod transport;
+/// Build and manipulate gemini URLs.
+pub mod urls;
 
+#[cfg(feature = "client")]
 pub use client::Client;
 pub use header::Header;
 pub use gemtext::Gemtext;
-pub use response::Response;
+#[cfg(feature = "client")]
+pub use redirect::{Redirect, RedirectPolicy};
+#[cfg(feature = "client")]
+pub use response::{ConnectionInfo, Response, Timing};
+#[cfg(feature = "client")]
+pub use titan::TitanRequest;
 
 use thiserror::Error;
 
+/// The port gemini servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 1965;
+
 /// Represents the different error types this module returns
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("Header is malformed: {0}")]
     HeaderFormat(String),
     #[error("Failed to parse URL: {0}")]
-    UrlParse(url::ParseError),
+    UrlParse(#[from] url::ParseError),
     #[error("The given URL didn't have a host: {0}")]
     UrlNoHost(String),
     #[error("The URL couldn't be resolved to an address: {0}")]
     UrlNoAddress(String),
+    #[error("Gemini URLs can't have a username or password: {0}")]
+    UrlUserinfo(String),
+    #[cfg(feature = "rustls")]
     #[error("Failed to create TLS client: {0}")]
-    TLSClient(rustls::Error),
+    TLSClient(#[from] rustls::Error),
+    #[cfg(feature = "native-tls")]
+    #[error("TLS connection failed: {0}")]
+    NativeTLS(#[from] native_tls::Error),
+    #[cfg(feature = "async")]
+    #[error("Async requests can only be made with a rustls connector")]
+    AsyncConnector,
+    #[error("The server name isn't a valid DNS name or IP address: {0}")]
+    ServerName(String),
     #[error("Couldn't connect to address {1}, TCP connection error: {0}")]
     TCPConnect(std::io::Error, String),
     #[error("Stream IO failure, {0}: {1}")]
     StreamIO(&'static str, std::io::Error),
+    #[error("The request didn't finish within its deadline of {0:?}")]
+    Timeout(std::time::Duration),
+    #[error("The request was cancelled")]
+    Cancelled,
+    #[error("Gave up after following {0} redirects, the last one to {1}")]
+    TooManyRedirects(usize, String),
     #[error("Malformed gemtext document: {0}")]
     GemtextFormat(String),
+    #[error("The response isn't a gemtext document: {0}")]
+    NotGemtext(String),
+    #[error("The certificate for {0} changed, {1} was trusted but {2} was presented")]
+    CertificateChanged(String, String, String),
+    #[error("Malformed known hosts file: {0}")]
+    KnownHostsFormat(String),
+    #[error("Failed to read or write known hosts file: {0}")]
+    KnownHostsIO(std::io::Error),
+}
+
+impl Error {
+    /// Returns whether the request that failed with this error might succeed if it's made again,
+    /// such as after a timeout, including a missed deadline, or a dropped connection.
+    ///
+    /// Errors in the request itself, like a malformed URL, and TLS failures, like a changed
+    /// certificate, aren't retriable. Responses with a temporary failure status aren't errors, see
+    /// [`header::StatusCode::is_retriable`] for those.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Error;
+    /// use std::io::ErrorKind;
+    ///
+    /// let timed_out = Error::StreamIO("Failed to read response from server", ErrorKind::TimedOut.into());
+    /// assert!(timed_out.is_retriable());
+    /// assert!(!Error::UrlNoHost(String::from("gemini:/path")).is_retriable());
+    /// ```
+    #[must_use]
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::TCPConnect(e, _) | Error::StreamIO(_, e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::WouldBlock
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::ConnectionRefused
+                    | std::io::ErrorKind::ConnectionReset
+                    | std::io::ErrorKind::ConnectionAborted
+                    | std::io::ErrorKind::BrokenPipe
+                    | std::io::ErrorKind::UnexpectedEof
+            ),
+            Error::Timeout(_) => true,
+            _ => false,
+        }
+    }
 }
@@ -1,35 +1,74 @@
-use std::io::{Read, Write};
-use std::net::{TcpStream, ToSocketAddrs};
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::io::{ErrorKind, Read, Write};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-use super::header::Header;
-use super::response::Response;
-use super::Error;
+use super::header::{Header, StatusCode};
+use super::redirect::{Redirect, RedirectPolicy};
+use super::response::{ConnectionInfo, Response, Timing};
+use super::titan::TitanRequest;
+use super::tls::{self, Connector};
+use super::transport::{Connection, Transport};
+use super::{urls, Error, DEFAULT_PORT};
+use crate::net::{self, ConnectError};
 
-use rustls::client::ServerCertVerifier;
 use url;
 
-struct NoCertVerification;
+/// The longest a valid header can be, a two digit status, a space, up to 1024 bytes of meta, and
+/// <CR><LF>.
+const MAX_HEADER_LEN: usize = 2 + 1 + 1024 + 2;
 
-impl ServerCertVerifier for NoCertVerification {
-    fn verify_server_cert(
-        &self,
-        _end_entity: &rustls::Certificate,
-        _intermediates: &[rustls::Certificate],
-        _server_name: &rustls::ServerName,
-        _scts: &mut dyn Iterator<Item = &[u8]>,
-        _ocsp_response: &[u8],
-        _now: std::time::SystemTime,
-    ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
-        Ok(rustls::client::ServerCertVerified::assertion())
-    }
-}
+/// The addresses each host resolved to, along with when it was resolved.
+type ResolvedHosts = HashMap<String, (Instant, Vec<SocketAddr>)>;
 
 /// Represents a client which will make gemini connections.
+///
+/// Clients are cheap to clone, and requests only need a shared reference, so one client can be
+/// shared between threads with an [`Arc`]. Clones share their connector, and with it the TLS
+/// sessions it can resume, see [`tls::RustlsConnector::set_session_cache`].
+///
+/// # Example
+///
+/// ```no_run
+/// use leda::gemini::Client;
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// let client = Arc::new(Client::new().unwrap());
+/// let handles: Vec<_> = ["gemini://example.org/", "gemini://example.com/"]
+///     .into_iter()
+///     .map(|url| {
+///         let client = client.clone();
+///         thread::spawn(move || client.request(String::from(url)))
+///     })
+///     .collect();
+/// ```
+#[derive(Clone)]
 pub struct Client {
-    tls_config: Arc<rustls::ClientConfig>,
+    connector: Arc<dyn Connector>,
     timeout: Option<Duration>,
+    proxy: Option<SocketAddr>,
+    transport: Option<Arc<dyn Transport>>,
+    rate_limit: Option<Duration>,
+    bandwidth_limit: Option<u64>,
+    max_body_size: Option<usize>,
+    deadline: Option<Duration>,
+    cancel: Option<Arc<AtomicBool>>,
+    default_port: u16,
+    server_name: Option<String>,
+    dns_cache: Option<Duration>,
+    nodelay: bool,
+    redirect_policy: RedirectPolicy,
+    max_redirects: usize,
+    /// The addresses each host resolved to and when, shared between clones so they all benefit
+    /// from each other's lookups.
+    pub(crate) resolved: Arc<Mutex<ResolvedHosts>>,
+    /// When the next request to each host may be sent, shared between clones so they're all
+    /// limited together.
+    next_request: Arc<Mutex<HashMap<String, Instant>>>,
 }
 
 impl Client {
@@ -45,7 +84,7 @@ impl Client {
     ///
     /// # Errors
     ///
-    /// Will return a [`Error::TLSClient`] if creating a TLS connector failed.
+    /// Will return an [`Error`](enum@Error) if creating a TLS connector failed.
     pub fn new() -> Result<Client, Error> {
         Self::with_timeout(None)
     }
@@ -60,22 +99,32 @@ impl Client {
     /// let client = Client::with_timeout(Some(Duration::new(5, 0)));
     /// ```
     ///
-    /// Will return a [`Error::TLSClient`] if creating a TLS connector failed.
+    /// Will return an [`Error`](enum@Error) if creating a TLS connector failed.
     pub fn with_timeout(timeout: Option<Duration>) -> Result<Client, Error> {
-        let tls_config = Arc::new(
-            rustls::ClientConfig::builder()
-                .with_safe_defaults()
-                .with_custom_certificate_verifier(Arc::new(NoCertVerification))
-                .with_no_client_auth(),
-        );
-
         Ok(Client {
-            tls_config,
+            connector: tls::default_connector()?,
             timeout,
+            proxy: None,
+            transport: None,
+            rate_limit: None,
+            bandwidth_limit: None,
+            max_body_size: None,
+            deadline: None,
+            cancel: None,
+            default_port: DEFAULT_PORT,
+            server_name: None,
+            dns_cache: None,
+            nodelay: true,
+            redirect_policy: RedirectPolicy::Never,
+            max_redirects: 5,
+            resolved: Arc::new(Mutex::new(HashMap::new())),
+            next_request: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
-    /// Sets the timeout for the client.
+    /// Sets the timeout for the client, how long connecting, and then each read or write, may
+    /// wait on the server before the request fails. If `timeout` is `None` the client waits as
+    /// long as it takes.
     ///
     /// # Examples
     ///
@@ -93,64 +142,892 @@ impl Client {
         self.timeout = timeout;
     }
 
-    fn parse_url(url: String) -> Result<(String, String), Error> {
-        let url_parsed = url::Url::parse(&url).map_err(Error::UrlParse)?;
+    /// Sets the longest a whole request may take, from connecting to reading the last of the
+    /// body. If `deadline` is `None` requests can take as long as the server keeps sending,
+    /// which is the default.
+    ///
+    /// This is separate from the timeout, which limits each step on its own, so a server that
+    /// trickles its response a byte at a time can't keep a request going forever. A request that
+    /// misses its deadline fails with [`Error::Timeout`]. Waiting for the rate limit doesn't
+    /// count towards the deadline.
+    ///
+    /// The deadline applies to each request on its own, so when redirects are followed every
+    /// request along the way gets the whole deadline, not what's left of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Give up on any request still going after 10 seconds
+    /// client.set_deadline(Some(Duration::from_secs(10)));
+    /// ```
+    pub fn set_deadline(&mut self, deadline: Option<Duration>) {
+        self.deadline = deadline;
+    }
+
+    /// Sets a flag that cancels requests in progress when it's set to `true`, making them fail
+    /// with [`Error::Cancelled`]. If `cancel` is `None` requests can't be cancelled, which is the
+    /// default.
+    ///
+    /// The flag is checked before connecting and between reads, so a read already waiting on the
+    /// server finishes first, set a timeout to bound how long that can take. The flag isn't reset
+    /// by the client, it has to be set back to `false` before the client's next request.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, Error};
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicBool, Ordering};
+    ///
+    /// let cancel = Arc::new(AtomicBool::new(false));
+    /// let mut client = Client::new().unwrap();
+    /// client.set_cancel_flag(Some(cancel.clone()));
+    ///
+    /// // Typically set from another thread, e.g. when the user presses escape
+    /// cancel.store(true, Ordering::SeqCst);
+    /// let result = client.request(String::from("gemini://example.org/"));
+    /// assert!(matches!(result, Err(Error::Cancelled)));
+    /// ```
+    pub fn set_cancel_flag(&mut self, cancel: Option<Arc<AtomicBool>>) {
+        self.cancel = cancel;
+    }
+
+    /// Returns whether the client's requests have been cancelled.
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+    }
+
+    /// Sets the SOCKS5 proxy the client connects through. If `proxy` is `None` the client connects
+    /// directly, which is the default.
+    ///
+    /// Host names are resolved by the proxy, and TLS is still negotiated with the server itself.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    /// use std::net::SocketAddr;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Connect through a local Tor daemon
+    /// client.set_proxy(Some(SocketAddr::from(([127, 0, 0, 1], 9050))));
+    /// ```
+    pub fn set_proxy(&mut self, proxy: Option<SocketAddr>) {
+        self.proxy = proxy;
+    }
+
+    /// Sets the TLS implementation the client secures its connections with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "rustls")]
+    /// # fn main() {
+    /// use leda::gemini::{Client, tls::RustlsConnector};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_connector(Arc::new(RustlsConnector::new()));
+    /// # }
+    /// # #[cfg(not(feature = "rustls"))]
+    /// # fn main() {}
+    /// ```
+    pub fn set_connector(&mut self, connector: Arc<dyn Connector>) {
+        self.connector = connector;
+    }
+
+    /// Sets the transport the client opens connections with. If `transport` is `None` the client
+    /// connects over TLS, which is the default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Answer every request with a canned response
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("51 Not found\r\n"))));
+    /// // Go back to connecting over TLS
+    /// client.set_transport(None);
+    /// ```
+    pub fn set_transport(&mut self, transport: Option<Arc<dyn Transport>>) {
+        self.transport = transport;
+    }
+
+    /// Sets the minimum interval between requests to the same host. If `rate_limit` is `None`
+    /// requests are sent as soon as they're made, which is the default.
+    ///
+    /// A request made too soon after the previous one to its host waits until the interval has
+    /// passed. Clones of a client share the time of each host's last request, so they're limited
+    /// together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // At most one request a second to each host
+    /// client.set_rate_limit(Some(Duration::from_secs(1)));
+    /// ```
+    pub fn set_rate_limit(&mut self, rate_limit: Option<Duration>) {
+        self.rate_limit = rate_limit;
+    }
+
+    /// Waits until a request may be sent to `host` under the client's rate limit, and reserves
+    /// the next slot for it.
+    fn wait_for_rate_limit(&self, host: &str) {
+        let Some(rate_limit) = self.rate_limit else {
+            return;
+        };
+
+        let now = Instant::now();
+        let send_at = {
+            let mut next_request = self.next_request.lock().unwrap();
+            let send_at = next_request.get(host).map_or(now, |next| (*next).max(now));
+            next_request.insert(host.to_string(), send_at + rate_limit);
+            send_at
+        };
+
+        thread::sleep(send_at - now);
+    }
+
+    /// Sets which redirects [`Client::request`] follows by itself, which is none of them unless
+    /// changed. A redirect that isn't followed is returned as the response.
+    ///
+    /// When a redirect is followed, the response returned is the one from where it led, and its
+    /// [`Response::url`] says where that is. The client can only request gemini URLs, so redirects
+    /// to other schemes are always returned, whatever the policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, RedirectPolicy};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_redirect_policy(RedirectPolicy::Confirm(Arc::new(|redirect| {
+    ///     println!("{} wants to send you to {}, allowing it", redirect.from, redirect.to);
+    ///     true
+    /// })));
+    /// ```
+    pub fn set_redirect_policy(&mut self, policy: RedirectPolicy) {
+        self.redirect_policy = policy;
+    }
+
+    /// Sets the most redirects a request follows in a row before failing with
+    /// [`Error::TooManyRedirects`], which is 5 unless changed, the limit the gemini spec
+    /// suggests.
+    pub fn set_max_redirects(&mut self, max_redirects: usize) {
+        self.max_redirects = max_redirects;
+    }
+
+    /// Makes a request with [`Client::get_data`], then follows the redirects the client's policy
+    /// allows.
+    fn get_following(
+        &self,
+        mut url: String,
+        progress: &mut dyn FnMut(usize),
+    ) -> Result<Response, Error> {
+        let mut response = self.get_data(&url, url.as_bytes(), progress)?;
+        let mut history = Vec::new();
+
+        while let StatusCode::Redirect(code) = response.header.status {
+            if matches!(self.redirect_policy, RedirectPolicy::Never) {
+                break;
+            }
+
+            let from = url::Url::parse(url.trim_end())?;
+            let Some(to) = response.header.redirect_url(from.as_str())? else {
+                break;
+            };
+            // Only gemini URLs can be requested, redirects anywhere else are for the caller.
+            if to.scheme() != "gemini" {
+                break;
+            }
+            let redirect = Redirect {
+                from: &from,
+                to: &to,
+                code,
+            };
+            if !self.redirect_policy.allows(&redirect) {
+                break;
+            }
+            if history.len() == self.max_redirects {
+                return Err(Error::TooManyRedirects(history.len(), to.to_string()));
+            }
+
+            #[cfg(feature = "tracing")]
+            tracing::debug!(from = %from, to = %to, "following redirect");
+
+            history.push((from, response.header.status));
+            url = format!("{}\r\n", to);
+            response = self.get_data(&url, url.as_bytes(), progress)?;
+        }
+
+        response.history = history;
+        Ok(response)
+    }
+
+    /// Sets how long the addresses a host resolves to are remembered for. If `ttl` is `None`
+    /// hosts are resolved again for every request, which is the default.
+    ///
+    /// The first request to a host resolves it, and later requests within `ttl` of that connect
+    /// to the same addresses without looking them up. Clones of a client share what's been
+    /// resolved. Hosts connected to through a proxy are resolved by the proxy, so they aren't
+    /// cached.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Look each host up at most once a minute
+    /// client.set_dns_cache(Some(Duration::from_secs(60)));
+    /// ```
+    pub fn set_dns_cache(&mut self, ttl: Option<Duration>) {
+        self.dns_cache = ttl;
+    }
+
+    /// Sets whether Nagle's algorithm is disabled, with `TCP_NODELAY`, on the connections the
+    /// client opens. It's disabled by default.
+    ///
+    /// A request is one small write followed by reading the response, so holding the write
+    /// back to batch it with more data only adds latency. Enabling it again is only worth it on
+    /// networks where many small packets are a problem.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_nodelay(false);
+    /// ```
+    pub fn set_nodelay(&mut self, nodelay: bool) {
+        self.nodelay = nodelay;
+    }
+
+    /// Resolves `host` to the addresses it can be reached at, reusing a previous lookup if the
+    /// client caches them and it hasn't expired.
+    pub(crate) fn resolve(&self, host: &str) -> Result<Vec<SocketAddr>, ConnectError> {
+        let Some(ttl) = self.dns_cache else {
+            return net::resolve(host);
+        };
+
+        let now = Instant::now();
+        if let Some((resolved_at, addresses)) = self.resolved.lock().unwrap().get(host) {
+            if now.duration_since(*resolved_at) < ttl {
+                return Ok(addresses.clone());
+            }
+        }
+
+        let addresses = net::resolve(host)?;
+        // A lookup that found nothing is worth retrying next time.
+        if !addresses.is_empty() {
+            self.resolved
+                .lock()
+                .unwrap()
+                .insert(host.to_string(), (now, addresses.clone()));
+        }
+
+        Ok(addresses)
+    }
+
+    /// Sets the most bytes per second the client reads of a response's body. If `bandwidth_limit`
+    /// is `None` bodies are read as fast as the server sends them, which is the default.
+    ///
+    /// The client sleeps between reads to keep to the limit. The timeout applies to each read on
+    /// its own, so time spent sleeping never causes a read to time out, but it does count
+    /// towards the deadline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Read bodies at no more than 64 KiB a second
+    /// client.set_bandwidth_limit(Some(64 * 1024));
+    /// ```
+    pub fn set_bandwidth_limit(&mut self, bandwidth_limit: Option<u64>) {
+        self.bandwidth_limit = bandwidth_limit.filter(|limit| *limit > 0);
+    }
+
+    /// Sets the most bytes of a response's body the client reads. If `max_body_size` is `None`
+    /// the whole body is read, which is the default.
+    ///
+    /// A body longer than this is cut off at the limit, and the response is marked as
+    /// [`Response::truncated`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("20 text/gemini\r\n# Hello\n"))));
+    /// client.set_max_body_size(Some(4));
+    ///
+    /// let response = client.request(String::from("gemini://example.org/")).unwrap();
+    /// assert_eq!(response.body.unwrap(), b"# He");
+    /// assert!(response.truncated);
+    /// ```
+    pub fn set_max_body_size(&mut self, max_body_size: Option<usize>) {
+        self.max_body_size = max_body_size;
+    }
+
+    /// Sets the port the client connects to when a URL doesn't specify one, which is
+    /// [`DEFAULT_PORT`] unless changed. A port given in the URL always takes precedence.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Client;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// // Connect to a development server on port 1966 unless told otherwise
+    /// client.set_default_port(1966);
+    /// ```
+    pub fn set_default_port(&mut self, port: u16) {
+        self.default_port = port;
+    }
+
+    /// Sets the name the client expects servers to identify as during the TLS handshake, which
+    /// is sent to them as the SNI. If `server_name` is `None` the URL's host is used, which is
+    /// the default.
+    ///
+    /// The connection is still made to the URL's host and port, this only changes who the
+    /// server is asked to be, such as when connecting to a load balancer or a test server by
+    /// address.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("20 text/gemini\r\n"))));
+    /// client.set_server_name(Some(String::from("example.org")));
+    ///
+    /// let response = client.request(String::from("gemini://192.0.2.1/")).unwrap();
+    /// let connection = response.connection.unwrap();
+    /// assert_eq!(connection.host, "192.0.2.1:1965");
+    /// assert_eq!(connection.server_name, "example.org");
+    /// ```
+    pub fn set_server_name(&mut self, server_name: Option<String>) {
+        self.server_name = server_name;
+    }
+
+    fn parse_url(&self, url: String) -> Result<(String, String), Error> {
+        let mut url_parsed = url::Url::parse(&url)?;
+        // The spec forbids userinfo, connecting anyway would silently drop it. The error holds
+        // the URL without it so credentials don't end up in logs.
+        if !url_parsed.username().is_empty() || url_parsed.password().is_some() {
+            let _ = url_parsed.set_username("");
+            let _ = url_parsed.set_password(None);
+            return Err(Error::UrlUserinfo(url_parsed.to_string()));
+        }
+        // The port is taken from the URL as given, normalizing drops the default port which
+        // would then be replaced by the client's.
+        let port = url_parsed.port().unwrap_or(self.default_port);
+        // Normalizing makes the host lowercase and drops any trailing dot, so the same host is
+        // always connected to, and rate limited, under the same name.
+        let url_parsed = urls::normalize(url_parsed);
         // We can't use ok_or_else here because that would consume `url` regardless of whether
         // the value is Some or None, and we use url later so it must not be moved.
         let host_str = match url_parsed.host_str() {
             Some(str) => str,
             None => return Err(Error::UrlNoHost(url)),
         };
-        let port = url_parsed.port().unwrap_or(1965);
+        let host = net::ascii_host(host_str)?;
 
-        Ok((format!("{}:{}", host_str, port), host_str.to_string()))
+        let server_name = match &self.server_name {
+            Some(server_name) => server_name.clone(),
+            None => net::server_name(&host),
+        };
+
+        Ok((format!("{}:{}", host, port), server_name))
     }
 
-    fn parse_response(response: &Vec<u8>) -> Result<Response, Error> {
-        // The Gemini protocol specifies that the response must have a header, and optionally a body
-        // which are separated by <CR><LF>. <CR><LF> must be there regardless of if a
-        // body exists.
-        let header_cutoff = {
-            let mut cutoff = None;
-            for i in 0..(response.len() - 1) {
-                if &response[i..=(i + 1)] == "\r\n".as_bytes() {
-                    cutoff = Some(i + 2);
-                    break;
+    /// Returns the index just past the first <CR><LF> in `buffer`, where the header ends.
+    fn header_end(buffer: &[u8]) -> Option<usize> {
+        buffer
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .map(|i| i + 2)
+    }
+
+    /// Returns the body of a response with the given header. Only successful responses have a
+    /// body, anything a server sends after any other header isn't part of the protocol and is
+    /// dropped.
+    fn response_body(header: &Header, body: Vec<u8>) -> Option<Vec<u8>> {
+        // Even if a body doesn't exist, rust will return an empty string for the body, we should
+        // check then if a body does or doesn't exist by checking if the body string is empty.
+        if body.is_empty() || !matches!(header.status, StatusCode::Success) {
+            None
+        } else {
+            Some(body)
+        }
+    }
+
+    fn missing_header_end() -> Error {
+        Error::HeaderFormat(String::from(
+            "There must be at least 1 <CR><LF> at the end of the header, but such a \
+        sequence was not found.",
+        ))
+    }
+
+    fn header_too_long(read: usize) -> Error {
+        Error::HeaderFormat(format!(
+            "No <CR><LF> was found in the first {} bytes of the response, which is longer than \
+            any valid header",
+            read
+        ))
+    }
+
+    /// Reads from `stream` until the end of the header and parses it, returning the header and
+    /// any bytes that were read past it.
+    ///
+    /// This stops as soon as the header is complete, so the status is known before any of the
+    /// body has to be read, and gives up once more has been read than a valid header can hold.
+    fn read_header<R: Read + ?Sized>(stream: &mut R) -> Result<(Header, Vec<u8>), Error> {
+        let (header, rest) = Self::read_raw_header(stream)?;
+
+        if !header.ends_with(b"\r\n") {
+            if header.len() >= MAX_HEADER_LEN {
+                return Err(Self::header_too_long(header.len()));
+            }
+            return Err(Self::missing_header_end());
+        }
+
+        let header = Header::try_from(String::from_utf8_lossy(&header).to_string())?;
+        Ok((header, rest))
+    }
+
+    /// Reads from `stream` until the first <CR><LF>, returning everything up to and including it
+    /// along with any bytes that were read past it.
+    ///
+    /// If the stream ends before a <CR><LF> is found, everything read so far is returned as the
+    /// header. If more has been read than a valid header can hold, the header is cut off at the
+    /// length of the longest valid one.
+    fn read_raw_header<R: Read + ?Sized>(stream: &mut R) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let mut buffer = Vec::new();
+        let mut chunk = [0; 1024];
+
+        loop {
+            let read = match stream.read(&mut chunk) {
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                // The header can't be complete if the connection was cut off before it ended.
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => 0,
+                Err(e) => return Err(Error::StreamIO("Failed to read response from server", e)),
+            };
+            if read == 0 {
+                return Ok((buffer, Vec::new()));
+            }
+
+            // Only what was just read needs to be searched, along with the last byte of the
+            // previous read in case the <CR><LF> was split between them.
+            let searched = buffer.len().saturating_sub(1);
+            buffer.extend_from_slice(&chunk[..read]);
+
+            if let Some(end) = Self::header_end(&buffer[searched..]) {
+                let rest = buffer.split_off(searched + end);
+                return Ok((buffer, rest));
+            }
+            if buffer.len() >= MAX_HEADER_LEN {
+                buffer.truncate(MAX_HEADER_LEN);
+                return Ok((buffer, Vec::new()));
+            }
+        }
+    }
+
+    /// Reads the rest of a response's body from `stream` onto the end of `body`, keeping to the
+    /// client's bandwidth limit and maximum body size. Returns whether the body might be
+    /// truncated, which is the case when the connection was closed without a close_notify or the
+    /// body was cut off at the maximum size.
+    ///
+    /// `progress` is called with the length of the body so far every time more of it is read.
+    fn read_body<R: Read + ?Sized>(
+        &self,
+        stream: &mut R,
+        body: &mut Vec<u8>,
+        progress: &mut dyn FnMut(usize),
+    ) -> Result<bool, Error> {
+        let max_body_size = self.max_body_size.unwrap_or(usize::MAX);
+        // Whatever was read along with the header may already be over the limit.
+        if body.len() > max_body_size {
+            body.truncate(max_body_size);
+            progress(body.len());
+            return Ok(true);
+        }
+        if !body.is_empty() {
+            progress(body.len());
+        }
+
+        let start = Instant::now();
+        let mut total = body.len() as u64;
+        // Small reads keep the pacing smooth when the limit is low.
+        let mut chunk = vec![
+            0;
+            self.bandwidth_limit.map_or(16 * 1024, |limit| {
+                usize::try_from(limit)
+                    .unwrap_or(usize::MAX)
+                    .clamp(1, 16 * 1024)
+            })
+        ];
+
+        loop {
+            let read = match stream.read(&mut chunk) {
+                Ok(0) => return Ok(false),
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                // A connection closed without a close_notify reads as an unexpected EOF, what was
+                // read up to that point is kept but flagged as possibly truncated.
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(true),
+                Err(e) => return Err(Error::StreamIO("Failed to read response from server", e)),
+            };
+            if body.len() + read > max_body_size {
+                body.extend_from_slice(&chunk[..max_body_size - body.len()]);
+                progress(body.len());
+                return Ok(true);
+            }
+            body.extend_from_slice(&chunk[..read]);
+            total += read as u64;
+            progress(body.len());
+
+            if let Some(limit) = self.bandwidth_limit {
+                // Sleep until reading this much would have taken at the limit, this happens
+                // between reads so it never counts towards the stream's read timeout.
+                let due = Duration::from_secs_f64(total as f64 / limit as f64);
+                let elapsed = start.elapsed();
+                if due > elapsed {
+                    thread::sleep(due - elapsed);
                 }
             }
+        }
+    }
 
-            cutoff
+    /// Opens a TLS connection to `host`, expecting the server to identify as `server_name`.
+    /// Returns the connection along with the address it was made to, if it's known.
+    ///
+    /// If there's a `deadline`, connecting and the TLS handshake give up once it's passed.
+    fn connect(
+        &self,
+        host: &str,
+        server_name: &str,
+        deadline: Option<Instant>,
+    ) -> Result<(Box<dyn Connection>, Option<SocketAddr>), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("connect", host, server_name).entered();
+
+        if let Some(transport) = &self.transport {
+            return Ok((transport.connect(host, server_name)?, None));
+        }
+
+        let timeout = shortest(self.timeout, self.time_left(deadline)?);
+        let stream = match self.proxy {
+            Some(proxy) => net::connect_socks5(proxy, host, timeout),
+            None => self
+                .resolve(host)
+                .and_then(|addresses| net::connect_any(net::interleave(addresses), timeout)),
         }
-        .ok_or_else(|| {
-            Error::HeaderFormat(String::from(
-                "There must be at least 1 <CR><LF> at the end of the header, but such a \
-            sequence was not found.",
-            ))
+        .map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(host.to_string()),
+            ConnectError::Io(e) => Error::TCPConnect(e, host.to_string()),
         })?;
 
-        let (header, body) = response.split_at(header_cutoff);
-        let header = String::from_utf8_lossy(header).to_string();
-        // Even if a body doesn't exist, rust will return an empty string for the body, we should
-        // check then if a body does or doesn't exist by checking if the body string is empty.
-        let body = if body.is_empty() {
-            None
+        stream
+            .set_nodelay(self.nodelay)
+            .map_err(|e| Error::TCPConnect(e, host.to_string()))?;
+
+        // Every read and write, the handshake's included, waits on the server for no longer than
+        // the timeout, and never past whatever's left of the deadline once connected.
+        if let Some(limit) = shortest(self.timeout, self.time_left(deadline)?) {
+            stream
+                .set_read_timeout(Some(limit))
+                .and_then(|_| stream.set_write_timeout(Some(limit)))
+                .map_err(|e| Error::TCPConnect(e, host.to_string()))?;
+        }
+
+        let address = stream.peer_addr().ok();
+
+        Ok((self.connector.connect(host, server_name, stream)?, address))
+    }
+
+    /// Returns how long is left until `deadline`, or `None` if there isn't one.
+    fn time_left(&self, deadline: Option<Instant>) -> Result<Option<Duration>, Error> {
+        let Some(deadline) = deadline else {
+            return Ok(None);
+        };
+
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            Err(Error::Timeout(self.deadline.unwrap_or_default()))
         } else {
-            Some(body.to_vec())
+            Ok(Some(remaining))
+        }
+    }
+
+    /// Turns an error from an IO operation into an [`Error::Cancelled`] if the request was
+    /// cancelled, or an [`Error::Timeout`] if it timed out because the request's deadline passed.
+    fn check_stopped(&self, error: Error, deadline: Option<Instant>) -> Error {
+        if self.is_cancelled() {
+            return Error::Cancelled;
+        }
+
+        let timed_out = match &error {
+            Error::TCPConnect(e, _) | Error::StreamIO(_, e) => {
+                matches!(e.kind(), ErrorKind::TimedOut | ErrorKind::WouldBlock)
+            }
+            _ => false,
+        };
+
+        match deadline {
+            Some(deadline) if timed_out && Instant::now() >= deadline => {
+                Error::Timeout(self.deadline.unwrap_or_default())
+            }
+            _ => error,
+        }
+    }
+
+    /// Connects to the server `url` points to, sends `request`, and parses the response, calling
+    /// `progress` as the body is read.
+    fn get_data(
+        &self,
+        url: &str,
+        request: &[u8],
+        progress: &mut dyn FnMut(usize),
+    ) -> Result<Response, Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("request", url = url.trim_end()).entered();
+
+        // Get the proper host string to connect to from the URL.
+        let (host, server_name) = self.parse_url(url.to_string())?;
+        self.wait_for_rate_limit(&host);
+
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        let start = Instant::now();
+        let deadline = self.deadline.map(|deadline| start + deadline);
+
+        let (mut tls, address) = self
+            .connect(&host, &server_name, deadline)
+            .map_err(|e| self.check_stopped(e, deadline))?;
+        let connected = Instant::now();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            host = %host,
+            address = ?address,
+            tls_version = tls.tls_version(),
+            "connected"
+        );
+
+        tls.write_all(request)
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))
+            .map_err(|e| self.check_stopped(e, deadline))?;
+
+        let mut tls = RequestReader {
+            connection: tls.as_mut(),
+            timeout: self.timeout,
+            deadline,
+            cancel: self.cancel.as_deref(),
         };
+        let (header, mut body) =
+            Self::read_header(&mut tls).map_err(|e| self.check_stopped(e, deadline))?;
+        #[cfg(feature = "tracing")]
+        tracing::debug!(status = ?header.status, meta = %header.meta, "received header");
+
+        // We can't parse the body as a string, we have no idea what it is.
+        let mut truncated = false;
+        if matches!(header.status, StatusCode::Success) {
+            truncated = self
+                .read_body(&mut tls, &mut body, progress)
+                .map_err(|e| self.check_stopped(e, deadline))?;
+        }
+        let transferred = Instant::now();
+        #[cfg(feature = "tracing")]
+        tracing::debug!(bytes = body.len(), truncated, "received body");
 
-        Ok(Response::new(Header::try_from(header)?, body))
+        // The handshake is long done by now, so the connection knows how it went.
+        let session_resumed = tls.connection.session_resumed();
+        // Everything has been read, so failing to say goodbye doesn't affect the response. The
+        // server may well have closed its end already.
+        let _ = tls.connection.shutdown();
+
+        let mut response = Response::new(header.clone(), Self::response_body(&header, body));
+        response.truncated = truncated;
+        response.timing = Some(Timing {
+            connect: connected - start,
+            transfer: transferred - connected,
+            total: start.elapsed(),
+        });
+        response.connection = Some(ConnectionInfo {
+            host,
+            server_name,
+            address,
+            session_resumed,
+        });
+        response.url = Some(url.trim_end_matches("\r\n").to_string());
+
+        Ok(response)
+    }
+
+    /// Gets the page at `url` without parsing the response, returning the header exactly as the
+    /// server sent it, including the `<CR><LF>`, and everything sent after it.
+    ///
+    /// This is meant for debugging servers, a header that [`Client::request`] would reject is
+    /// still returned. If the server never sent a `<CR><LF>`, everything it sent is returned as the
+    /// header, up to the length of the longest valid header, and the body is `None`.
+    ///
+    /// The client's timeout, deadline, cancel flag, and maximum body size apply just as they do
+    /// to [`Client::request`], a body longer than the maximum is cut off at it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("2 text/gemini\r\n# Hi\n"))));
+    ///
+    /// let (header, body) = client.request_raw(String::from("gemini://example.org/")).unwrap();
+    /// assert_eq!(header, "2 text/gemini\r\n");
+    /// assert_eq!(body.unwrap(), b"# Hi\n");
+    ///
+    /// client.set_max_body_size(Some(2));
+    /// let (_header, body) = client.request_raw(String::from("gemini://example.org/")).unwrap();
+    /// assert_eq!(body.unwrap(), b"# ");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url or
+    /// communicating with the server.
+    pub fn request_raw(&self, mut url: String) -> Result<(String, Option<Vec<u8>>), Error> {
+        if !url.ends_with("\r\n") {
+            url += "\r\n";
+        }
+
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("request_raw", url = url.trim_end()).entered();
+
+        let (host, server_name) = self.parse_url(url.clone())?;
+        self.exchange_raw(&host, &server_name, url.as_bytes())
+    }
+
+    /// Connects to `host` and sends `line` exactly as given, returning the response the way
+    /// [`Client::request_raw`] does.
+    ///
+    /// Nothing is added to or checked about `line`, not even a `<CR><LF>`, so it can be used to
+    /// see how servers handle malformed requests. `host` is in the form `<host>[:<port>]`, the
+    /// client's default port is used if there's none, and it's also the name the server is
+    /// expected to identify as unless the client was given another with
+    /// [`Client::set_server_name`].
+    ///
+    /// A misbehaving server is held to the same limits as with [`Client::request_raw`], so one
+    /// that never stops sending is cut off at the client's maximum body size or deadline.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// let transport = Arc::new(MemoryTransport::new("59 Bad request\r\n"));
+    /// client.set_transport(Some(transport.clone()));
+    ///
+    /// let (header, body) = client.send_raw_request("example.org", b"not a url\n").unwrap();
+    /// assert_eq!(header, "59 Bad request\r\n");
+    /// assert_eq!(body, None);
+    /// assert_eq!(transport.requests(), [b"not a url\n".to_vec()]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if `host` isn't a valid host or there was a problem
+    /// communicating with the server.
+    pub fn send_raw_request(
+        &self,
+        host: &str,
+        line: &[u8],
+    ) -> Result<(String, Option<Vec<u8>>), Error> {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("send_raw_request", host).entered();
+
+        let (host, server_name) = self.parse_url(format!("gemini://{}/", host))?;
+        self.exchange_raw(&host, &server_name, line)
+    }
+
+    /// Connects to `host`, sends `request`, and reads back the raw header and body.
+    fn exchange_raw(
+        &self,
+        host: &str,
+        server_name: &str,
+        request: &[u8],
+    ) -> Result<(String, Option<Vec<u8>>), Error> {
+        self.wait_for_rate_limit(host);
+
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        let deadline = self.deadline.map(|deadline| Instant::now() + deadline);
+        let (mut tls, _address) = self
+            .connect(host, server_name, deadline)
+            .map_err(|e| self.check_stopped(e, deadline))?;
+        tls.write_all(request)
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))
+            .map_err(|e| self.check_stopped(e, deadline))?;
+
+        // The response is read with the same limits as any other, as a server being debugged is
+        // the least likely to behave.
+        let mut tls = RequestReader {
+            connection: tls.as_mut(),
+            timeout: self.timeout,
+            deadline,
+            cancel: self.cancel.as_deref(),
+        };
+        let (header, mut body) =
+            Self::read_raw_header(&mut tls).map_err(|e| self.check_stopped(e, deadline))?;
+        if header.ends_with(b"\r\n") {
+            self.read_body(&mut tls, &mut body, &mut |_| {})
+                .map_err(|e| self.check_stopped(e, deadline))?;
+        }
+        let _ = tls.connection.shutdown();
+
+        let body = if body.is_empty() { None } else { Some(body) };
+        Ok((String::from_utf8_lossy(&header).to_string(), body))
     }
 
     /// Gets the page at `url`.
     ///
-    /// The given url must start with the scheme `"gemini://"`
+    /// The given url must start with the scheme `"gemini://"`. Redirects are followed if the
+    /// client's [`Client::set_redirect_policy`] allows it.
     ///
     /// # Examples
     ///
     /// ```
     /// use leda::gemini::Client;
     ///
-    /// let mut client = Client::new().unwrap();
+    /// let client = Client::new().unwrap();
     /// let response = client.request(String::from("gemini://gemini.circumlunar.space/"));
     /// ```
     ///
@@ -158,89 +1035,380 @@ impl Client {
     ///
     /// Will return an [`Error`] if there was a problem with parsing the url, communicating with
     /// the server, or with parsing the servers response.
-    pub fn request(&mut self, mut url: String) -> Result<Response, Error> {
-        // Get the proper host string to connect to from the URL.
-        let (host, server_name) = Self::parse_url(url.clone())?;
-
-        // Connect to the server and establish a TLS connection.
-        let rustls_server_name = server_name.as_str().try_into().unwrap();
-        let mut conn =
-            rustls::ClientConnection::new(self.tls_config.clone(), rustls_server_name).unwrap();
-
-        // Connect, with timeout if requested
-        let mut stream = if let Some(timeout) = self.timeout {
-            // Get all host addresses so we can attempt to connect to till we get a successful connection
-            let mut addresses = host
-                .to_socket_addrs()
-                .map_err(|e| Error::TCPConnect(e, (&host).clone()))?
-                .peekable();
-            if addresses.peek().is_none() {
-                return Err(Error::UrlNoAddress(host));
-            }
-
-            // do this to shut the compiler up, we'll unwrap later because we know it has something in it
-            let mut result = None;
-            for address in addresses {
-                match TcpStream::connect_timeout(&address, timeout) {
-                    Ok(r) => {
-                        result = Some(Ok(r));
-                        break;
-                    }
-                    Err(e) => result = Some(Err(e)),
-                }
-            }
-            result.unwrap()
-        } else {
-            TcpStream::connect(host.clone())
+    pub fn request(&self, mut url: String) -> Result<Response, Error> {
+        // Check that the URL given to us is proper, the Gemini protocol specifies all URL requests
+        // must end in <CR><LF>.
+        if !url.ends_with("\r\n") {
+            url += "\r\n";
         }
-        .map_err(|e| Error::TCPConnect(e, (&host).clone()))?;
 
-        let mut tls = rustls::Stream::new(&mut conn, &mut stream);
+        self.get_following(url, &mut |_| {})
+    }
 
-        // Check that the URL given to us is proper, the Gemini protocol specifies all URL requests
-        // must end in <CR><LF>.
+    /// Gets the page at `url` like [`Client::request`], returning the header and the body as
+    /// [`bytes::Bytes`], which can be cloned and sliced without copying the body.
+    ///
+    /// Use [`Response::into_bytes`] instead to keep the rest of the response.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("20 text/gemini\r\n# Hi\n"))));
+    ///
+    /// let (header, body) = client.request_bytes(String::from("gemini://example.org/")).unwrap();
+    /// let shared = body.unwrap();
+    /// assert_eq!(header.meta, "text/gemini");
+    /// assert_eq!(shared.slice(2..), "Hi\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`] if there was a problem with parsing the url, communicating with
+    /// the server, or with parsing the servers response.
+    #[cfg(feature = "bytes")]
+    pub fn request_bytes(&self, url: String) -> Result<(Header, Option<bytes::Bytes>), Error> {
+        let response = self.request(url)?;
+
+        Ok((response.header, response.body.map(bytes::Bytes::from)))
+    }
+
+    /// Gets the page at `url` like [`Client::request`], calling `progress` with the number of
+    /// bytes of the body read so far each time more of it arrives.
+    ///
+    /// Gemini responses don't say how long their bodies are, so this can drive a count of bytes
+    /// downloaded but not a percentage. `progress` is only called for successful responses.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("20 text/gemini\r\n# Hi\n"))));
+    ///
+    /// let mut downloaded = 0;
+    /// client
+    ///     .request_with_progress(String::from("gemini://example.org/"), |read| downloaded = read)
+    ///     .unwrap();
+    /// assert_eq!(downloaded, 5);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url,
+    /// communicating with the server, or with parsing the servers response.
+    pub fn request_with_progress(
+        &self,
+        mut url: String,
+        mut progress: impl FnMut(usize),
+    ) -> Result<Response, Error> {
         if !url.ends_with("\r\n") {
             url += "\r\n";
         }
 
-        tls.write(url.as_bytes())
-            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+        self.get_following(url, &mut progress)
+    }
+
+    /// Gets the pages at each of `urls`, making at most `max_concurrency` requests at a time, and
+    /// returns their results in the same order as `urls`.
+    ///
+    /// Each request is made on a thread of its own, up to `max_concurrency` of them, which bounds
+    /// the number of open connections. A `max_concurrency` of `0` is treated as `1`. Requests to
+    /// the same host still wait for each other if there's a rate limit, see
+    /// [`Client::set_rate_limit`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Client, transport::MemoryTransport};
+    /// use std::sync::Arc;
+    ///
+    /// let mut client = Client::new().unwrap();
+    /// client.set_transport(Some(Arc::new(MemoryTransport::new("20 text/gemini\r\n# Hi\n"))));
+    ///
+    /// let urls = ["gemini://example.org/", "gemini://example.com/"].map(String::from);
+    /// let responses = client.request_many(urls, 2);
+    /// let hosts: Vec<_> = responses
+    ///     .iter()
+    ///     .map(|response| response.as_ref().unwrap().connection.as_ref().unwrap().host.as_str())
+    ///     .collect();
+    /// assert_eq!(hosts, ["example.org:1965", "example.com:1965"]);
+    /// ```
+    #[must_use]
+    pub fn request_many(
+        &self,
+        urls: impl IntoIterator<Item = String>,
+        max_concurrency: usize,
+    ) -> Vec<Result<Response, Error>> {
+        let urls: Vec<String> = urls.into_iter().collect();
+        let next = AtomicUsize::new(0);
+        let workers = max_concurrency.clamp(1, urls.len().max(1));
+
+        let mut results: Vec<Option<Result<Response, Error>>> = urls.iter().map(|_| None).collect();
+        thread::scope(|scope| {
+            let handles: Vec<_> = (0..workers)
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut done = Vec::new();
+                        // Each worker takes the next URL nobody has started on yet.
+                        loop {
+                            let index = next.fetch_add(1, Ordering::Relaxed);
+                            let Some(url) = urls.get(index) else {
+                                break;
+                            };
+                            done.push((index, self.request(url.clone())));
+                        }
+                        done
+                    })
+                })
+                .collect();
+
+            for handle in handles {
+                let done = handle
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+                for (index, result) in done {
+                    results[index] = Some(result);
+                }
+            }
+        });
+
+        results.into_iter().flatten().collect()
+    }
 
-        // We can't parse this as a string yet, we can be confident-ish that the header is UTF-8,
-        // but we have no idea what the body is.
-        let mut response = Vec::new();
-        tls.read_to_end(&mut response)
-            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+    /// Uploads the data in `request` to the titan URL it was created with.
+    ///
+    /// Titan servers respond with a regular gemini header, usually redirecting to where the
+    /// uploaded resource can be found.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::gemini::{Client, TitanRequest};
+    ///
+    /// let client = Client::new().unwrap();
+    /// let request = TitanRequest::new(String::from("titan://example.org/notes.gmi"),
+    ///                                 b"# My notes".to_vec())
+    ///     .token(String::from("hunter2"));
+    /// let response = client.titan(request);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url,
+    /// communicating with the server, or with parsing the servers response.
+    pub fn titan(&self, request: TitanRequest) -> Result<Response, Error> {
+        let mut data = request.request_line().into_bytes();
+        data.extend_from_slice(request.data());
 
-        Ok(Self::parse_response(&response)?)
+        self.get_data(request.url(), &data, &mut |_| {})
     }
 
+    /// Gets the page at `url` like [`Client::request`], without blocking the thread.
+    ///
+    /// The client's connector has to be a [`RustlsConnector`](super::tls::RustlsConnector), as
+    /// that's the only TLS implementation that can be driven asynchronously, and its certificate
+    /// policy is enforced just as it is for blocking requests. The timeout, deadline, cancel
+    /// flag, and maximum body size apply as well, but unlike blocking requests these don't:
+    ///
+    /// - Redirects are never followed, they're returned as the response.
+    /// - The host is connected to directly, the proxy, transport, and DNS cache are ignored.
+    /// - The rate limit and bandwidth limit are ignored, keeping to them means sleeping, which
+    ///   would block the executor.
+    /// - The response has no [`Response::timing`] or [`Response::connection`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::AsyncConnector`] if the client's connector doesn't use rustls, or
+    /// an [`Error`](enum@Error) if there was a problem with parsing the url, communicating with
+    /// the server, or with parsing the servers response.
     #[cfg(feature = "async")]
-    pub async fn async_request(&mut self, mut url: String) -> Result<Response, Error> {
-        use async_std::net::TcpStream;
-        use async_std::io::{WriteExt, ReadExt};
+    pub async fn async_request(&self, url: String) -> Result<Response, Error> {
+        match self.deadline {
+            Some(deadline) => async_std::future::timeout(deadline, self.async_exchange(url))
+                .await
+                .unwrap_or(Err(Error::Timeout(deadline))),
+            None => self.async_exchange(url).await,
+        }
+    }
+
+    /// Makes the request for [`Client::async_request`], which bounds how long it takes.
+    #[cfg(feature = "async")]
+    async fn async_exchange(&self, mut url: String) -> Result<Response, Error> {
         use async_rustls::TlsConnector;
+        use async_std::io::{ReadExt, WriteExt};
+        use async_std::net::TcpStream;
         use rustls::ServerName;
 
-        let (host, server_name) = Self::parse_url(url.clone())?;
-        let server_name = ServerName::try_from(server_name.as_str()).unwrap();
-        // We can't respect timeout here, doesn't work in async
-        let stream = TcpStream::connect(host.clone()).await
-            .map_err(|e| Error::TCPConnect(e, (&host).clone()))?;
-        let connector = TlsConnector::from(self.tls_config.clone());
-        let mut stream = connector.connect(server_name, stream).await
-            .map_err(|e| Error::StreamIO("Connector call failed after creating stream", e))?;
+        let rustls = self.connector.as_rustls().ok_or(Error::AsyncConnector)?;
+        let (host, server_name) = self.parse_url(url.clone())?;
+        let rustls_server_name = ServerName::try_from(server_name.as_str())
+            .map_err(|_| Error::ServerName(server_name.clone()))?;
+
+        if self.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        let stream = within(self.timeout, TcpStream::connect(host.as_str()))
+            .await
+            .map_err(|e| Error::TCPConnect(e, host.clone()))?;
+        stream
+            .set_nodelay(self.nodelay)
+            .map_err(|e| Error::TCPConnect(e, host.clone()))?;
+        let connector = TlsConnector::from(rustls.config().clone());
+        let mut stream = within(self.timeout, connector.connect(rustls_server_name, stream))
+            .await
+            .map_err(|e| Error::StreamIO("TLS handshake failed", e))?;
+        rustls.check_known_host(&host, stream.get_ref().1)?;
 
         if !url.ends_with("\r\n") {
             url += "\r\n";
         }
-        stream.write(url.as_bytes()).await
+        within(self.timeout, stream.write_all(url.as_bytes()))
+            .await
             .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+        within(self.timeout, stream.flush())
+            .await
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+
+        // Only the header is read at first, as in the blocking requests, so the body of a
+        // response that isn't successful is never read.
+        let mut buffer = Vec::new();
+        let mut chunk = [0; 1024];
+        let header_cutoff = loop {
+            if let Some(end) = Self::header_end(&buffer) {
+                break end;
+            }
+            if buffer.len() >= MAX_HEADER_LEN {
+                return Err(Self::header_too_long(buffer.len()));
+            }
+            if self.is_cancelled() {
+                return Err(Error::Cancelled);
+            }
 
-        let mut response = Vec::new();
-        stream.read_to_end(&mut response).await
-            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+            let read = match within(self.timeout, stream.read(&mut chunk)).await {
+                Ok(read) => read,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                // The header can't be complete if the connection was cut off before it ended.
+                Err(e) if e.kind() == ErrorKind::UnexpectedEof => 0,
+                Err(e) => return Err(Error::StreamIO("Failed to read response from server", e)),
+            };
+            if read == 0 {
+                return Err(Self::missing_header_end());
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+        };
+        let mut body = buffer.split_off(header_cutoff);
+        let header = Header::try_from(String::from_utf8_lossy(&buffer).to_string())?;
+
+        let max_body_size = self.max_body_size.unwrap_or(usize::MAX);
+        let mut truncated = false;
+        if matches!(header.status, StatusCode::Success) {
+            loop {
+                if body.len() > max_body_size {
+                    body.truncate(max_body_size);
+                    truncated = true;
+                    break;
+                }
+                if self.is_cancelled() {
+                    return Err(Error::Cancelled);
+                }
 
-        Ok(Self::parse_response(&response)?)
+                let read = match within(self.timeout, stream.read(&mut chunk)).await {
+                    Ok(read) => read,
+                    Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                    // The connection was closed without a close_notify.
+                    Err(e) if e.kind() == ErrorKind::UnexpectedEof => {
+                        truncated = true;
+                        break;
+                    }
+                    Err(e) => {
+                        return Err(Error::StreamIO("Failed to read response from server", e))
+                    }
+                };
+                if read == 0 {
+                    break;
+                }
+                body.extend_from_slice(&chunk[..read]);
+            }
+        }
+
+        let mut response = Response::new(header.clone(), Self::response_body(&header, body));
+        response.truncated = truncated;
+        response.url = Some(url.trim_end_matches("\r\n").to_string());
+
+        Ok(response)
+    }
+}
+
+/// Waits for `operation` to finish for no longer than `timeout`, if there is one.
+#[cfg(feature = "async")]
+async fn within<T>(
+    timeout: Option<Duration>,
+    operation: impl std::future::Future<Output = std::io::Result<T>>,
+) -> std::io::Result<T> {
+    match timeout {
+        Some(timeout) => async_std::io::timeout(timeout, operation).await,
+        None => operation.await,
+    }
+}
+
+/// Returns the shorter of two optional time limits, or whichever there is.
+fn shortest(a: Option<Duration>, b: Option<Duration>) -> Option<Duration> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+/// Wraps a connection so that no read waits for the server for longer than `timeout` or past
+/// `deadline`, and no read starts once `cancel` is set, if there are any.
+struct RequestReader<'a> {
+    connection: &'a mut dyn Connection,
+    timeout: Option<Duration>,
+    deadline: Option<Instant>,
+    cancel: Option<&'a AtomicBool>,
+}
+
+impl Read for RequestReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self
+            .cancel
+            .is_some_and(|cancel| cancel.load(Ordering::SeqCst))
+        {
+            return Err(std::io::Error::other("Request was cancelled"));
+        }
+        let remaining = match self.deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Err(ErrorKind::TimedOut.into());
+                }
+                Some(remaining)
+            }
+            None => None,
+        };
+        if let Some(limit) = shortest(self.timeout, remaining) {
+            self.connection.set_read_timeout(Some(limit))?;
+        }
+
+        self.connection.read(buf)
+    }
+}
+
+impl Default for Client {
+    /// Creates a client with no timeout, see [`Client::new`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the default TLS connector couldn't be created, which can only happen when
+    /// using the `native-tls` backend.
+    fn default() -> Self {
+        Self::new().expect("Failed to create the default TLS connector")
     }
 }
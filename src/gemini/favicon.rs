@@ -0,0 +1,111 @@
+use super::header::StatusCode;
+use super::{Client, Error};
+
+/// Zero width joiner, which joins emoji into a single one, like a family out of its members.
+const ZWJ: char = '\u{200d}';
+
+/// Checks the contents of a capsule's `favicon.txt`, returning its emoji if it holds exactly
+/// one, surrounding whitespace aside.
+///
+/// An emoji may be made of several characters, such as one with a skin tone, a flag, or
+/// several emoji joined by zero width joiners, but anything that would show as more than one
+/// symbol is rejected.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::favicon;
+///
+/// assert_eq!(favicon::parse("🚀\n").as_deref(), Some("🚀"));
+/// assert_eq!(favicon::parse("👩🏽‍🚀").as_deref(), Some("👩🏽‍🚀"));
+/// assert_eq!(favicon::parse("🇳🇿").as_deref(), Some("🇳🇿"));
+/// assert_eq!(favicon::parse("🚀🚀"), None);
+/// assert_eq!(favicon::parse("# My capsule"), None);
+/// assert_eq!(favicon::parse(""), None);
+/// ```
+#[must_use]
+pub fn parse(text: &str) -> Option<String> {
+    let text = text.trim();
+    let single = !text.is_empty() && text.split(ZWJ).all(is_single_symbol);
+
+    single.then(|| text.to_string())
+}
+
+/// Returns whether `text` is one symbol, a character followed only by characters that modify
+/// it, or a pair of regional indicators making a flag.
+fn is_single_symbol(text: &str) -> bool {
+    let mut chars = text.chars();
+    let Some(first) = chars.next() else {
+        return false;
+    };
+    if first.is_whitespace() || first.is_control() {
+        return false;
+    }
+
+    let rest: Vec<char> = chars.collect();
+    match rest.as_slice() {
+        [second] if is_regional_indicator(first) && is_regional_indicator(*second) => true,
+        rest => rest.iter().all(|&c| is_modifier(c)),
+    }
+}
+
+/// Returns whether `c` is one of the letters flags are spelled with.
+fn is_regional_indicator(c: char) -> bool {
+    ('\u{1f1e6}'..='\u{1f1ff}').contains(&c)
+}
+
+/// Returns whether `c` changes how the character before it looks rather than standing on its own.
+fn is_modifier(c: char) -> bool {
+    matches!(c,
+        // Combining marks
+        '\u{300}'..='\u{36f}'
+        // Variation selectors, which pick between text and emoji presentation
+        | '\u{fe00}'..='\u{fe0f}'
+        // The keycap that turns a digit into an emoji
+        | '\u{20e3}'
+        // Skin tones
+        | '\u{1f3fb}'..='\u{1f3ff}'
+        // Tags, which spell out subdivision flags
+        | '\u{e0020}'..='\u{e007f}'
+    )
+}
+
+/// Fetches the emoji the capsule `url` is on uses as its icon, which by convention is served as
+/// `/favicon.txt`.
+///
+/// Returns `None` if the capsule doesn't successfully serve a `favicon.txt`, such as with a
+/// `51 Not Found`, or if it doesn't hold a single emoji, see [`parse`].
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::{Client, favicon, transport::MemoryTransport};
+/// use std::sync::Arc;
+///
+/// let mut client = Client::new().unwrap();
+/// let transport = Arc::new(MemoryTransport::new("20 text/plain\r\n🌻\n"));
+/// client.set_transport(Some(transport.clone()));
+///
+/// let icon = favicon::fetch(&client, "gemini://example.org/garden/index.gmi").unwrap();
+/// assert_eq!(icon.as_deref(), Some("🌻"));
+/// assert_eq!(transport.requests(), [b"gemini://example.org/favicon.txt\r\n".to_vec()]);
+///
+/// client.set_transport(Some(Arc::new(MemoryTransport::new("51 Not found\r\n"))));
+/// assert_eq!(favicon::fetch(&client, "gemini://example.org/").unwrap(), None);
+/// ```
+///
+/// # Errors
+///
+/// Will return an [`Error`](enum@Error) if `url` is malformed or the request failed.
+pub fn fetch(client: &Client, url: &str) -> Result<Option<String>, Error> {
+    let mut favicon_url = url::Url::parse(url)?;
+    favicon_url.set_path("/favicon.txt");
+    favicon_url.set_query(None);
+    favicon_url.set_fragment(None);
+
+    let response = client.request(favicon_url.to_string())?;
+    match (response.header.status, response.text()) {
+        (StatusCode::Success, Some(text)) => Ok(parse(&text)),
+        _ => Ok(None),
+    }
+}
@@ -1,7 +1,38 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
 use super::Error;
 
+/// Render gemtext for ANSI terminals.
+mod ansi;
+/// Build gemtext documents in code.
+mod builder;
+/// Compare versions of a document.
+mod diff;
+/// Render gemtext as HTML.
+mod html;
+/// Render gemtext as markdown.
+mod markdown;
+/// Outline a document by its headings.
+mod outline;
+/// Render gemtext as plain text.
+mod plain;
+/// Gather statistics about a document.
+mod stats;
+/// Check a document for problems before it's published.
+mod validate;
+
+pub use builder::GemtextBuilder;
+pub use diff::Change;
+pub use html::HtmlOptions;
+pub use outline::OutlineEntry;
+pub use plain::LinkStyle;
+pub use stats::GemtextStats;
+pub use validate::{ValidationWarning, WarningKind, MAX_LINE_LENGTH};
+
 /// Represents a gemtext document by element, line by line.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Gemtext {
     /// List of elements.
     pub elements: Vec<Element>,
@@ -9,12 +40,17 @@ pub struct Gemtext {
 
 /// Represents the varying elements a gemtext document can have.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Element {
     /// Text without any specific formatting, to be treated like a paragraph
     Text(String),
+    /// A blank line, or one holding only whitespace, which only adds vertical space
+    Blank,
     /// A link, the first member of the tuple is where the link goes to, the
     /// second member is the human readable text to display for this link.
-    Link(String, String),
+    /// The text is `None` when the line gave none, renderers show the URL
+    /// itself in its place.
+    Link(String, Option<String>),
     /// Header
     Heading(String),
     /// Sub-header
@@ -25,13 +61,181 @@ pub enum Element {
     UnorderedList(Vec<String>),
     /// A block quote
     BlockQuote(String),
-    /// The first element is the alt text, the second element is the preformatted text
+    /// The first element is the alt text, the second element is the preformatted text. The text
+    /// is kept byte for byte as it was in the document, tabs and line endings included, and always
+    /// ends with a newline unless it's empty.
     Preformatted(String, String),
 }
 
+impl Element {
+    /// Creates a line of text.
+    #[must_use]
+    pub fn text(text: impl Into<String>) -> Element {
+        Element::Text(text.into())
+    }
+
+    /// Creates a link to `url` shown as `text`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::Element;
+    ///
+    /// let link = Element::link("gemini://example.org/", "Example");
+    /// assert_eq!(link.to_string(), "=> gemini://example.org/ Example");
+    /// ```
+    #[must_use]
+    pub fn link(url: impl Into<String>, text: impl Into<String>) -> Element {
+        Element::Link(url.into(), Some(text.into()))
+    }
+
+    /// Creates a link to `url` without any text, readers show the URL itself.
+    #[must_use]
+    pub fn bare_link(url: impl Into<String>) -> Element {
+        Element::Link(url.into(), None)
+    }
+
+    /// Creates a top level heading.
+    #[must_use]
+    pub fn heading(text: impl Into<String>) -> Element {
+        Element::Heading(text.into())
+    }
+
+    /// Creates a second level heading.
+    #[must_use]
+    pub fn subheading(text: impl Into<String>) -> Element {
+        Element::Subheading(text.into())
+    }
+
+    /// Creates a third level heading.
+    #[must_use]
+    pub fn subsubheading(text: impl Into<String>) -> Element {
+        Element::Subsubheading(text.into())
+    }
+
+    /// Creates a list with the given items.
+    #[must_use]
+    pub fn list<I>(items: I) -> Element
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        Element::UnorderedList(items.into_iter().map(Into::into).collect())
+    }
+
+    /// Creates a block quote.
+    #[must_use]
+    pub fn quote(text: impl Into<String>) -> Element {
+        Element::BlockQuote(text.into())
+    }
+
+    /// Creates a preformatted block with the given alt text.
+    #[must_use]
+    pub fn preformatted(alt_text: impl Into<String>, block: impl Into<String>) -> Element {
+        Element::Preformatted(alt_text.into(), block.into())
+    }
+
+    /// Returns the alt text of a preformatted block, trimmed, or `None` for any other element
+    /// or a block without alt text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("``` A drawing of a cat \n=^.^=\n```").unwrap();
+    /// assert_eq!(doc.elements[0].alt_text(), Some("A drawing of a cat"));
+    /// ```
+    #[must_use]
+    pub fn alt_text(&self) -> Option<&str> {
+        match self {
+            Element::Preformatted(alt_text, _block) => {
+                Some(alt_text.trim()).filter(|alt_text| !alt_text.is_empty())
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the language a preformatted block is written in, if its alt text names one, as a
+    /// hint for syntax highlighting.
+    ///
+    /// By convention a block's alt text is a language name, like `rust`, when it holds code.
+    /// Alt text that's a single word of letters, digits, and `+`, `#`, `-`, `_`, or `.` is taken
+    /// to be a language, anything longer is taken to be a description.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("```rust\nfn main() {}\n```\n```A cat\n=^.^=\n```").unwrap();
+    /// assert_eq!(doc.elements[0].language(), Some("rust"));
+    /// assert_eq!(doc.elements[1].language(), None);
+    /// ```
+    #[must_use]
+    pub fn language(&self) -> Option<&str> {
+        self.alt_text().filter(|alt_text| {
+            alt_text
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '+' | '#' | '-' | '_' | '.'))
+        })
+    }
+}
+
+/// Controls how [`Gemtext::parse_with`] treats the parts of a document that can reasonably be
+/// read more than one way. The default options are the ones [`Gemtext::new`] uses.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::gemtext::{Element, Gemtext, ParseOptions};
+///
+/// let options = ParseOptions {
+///     coalesce_quotes: true,
+///     trim_headings: true,
+///     ..ParseOptions::default()
+/// };
+/// let doc = Gemtext::parse_with("# Quotes\n> one\n> two", &options).unwrap();
+/// assert_eq!(doc.elements[0], Element::Heading(String::from("Quotes")));
+/// assert_eq!(doc.elements[1], Element::BlockQuote(String::from(" one\n two")));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Keep malformed lines, such as a link with nothing after the `=>`, as [`Element::Text`]
+    /// instead of failing to parse the document. Off by default.
+    pub lenient: bool,
+    /// Merge consecutive quote lines into one [`Element::BlockQuote`], with their text joined by
+    /// newlines. Off by default, every quote line is its own element.
+    pub coalesce_quotes: bool,
+    /// Parse lines that are empty or only whitespace as [`Element::Blank`]. On by default, when
+    /// off they're parsed as [`Element::Text`] holding the line as it was written.
+    pub blank_lines: bool,
+    /// Trim the whitespace around the text of headings. Off by default, the text keeps the
+    /// whitespace between it and the `#`.
+    pub trim_headings: bool,
+}
+
+impl Default for ParseOptions {
+    fn default() -> ParseOptions {
+        ParseOptions {
+            lenient: false,
+            coalesce_quotes: false,
+            blank_lines: true,
+            trim_headings: false,
+        }
+    }
+}
+
+/// Line numbers paired with a description of what was wrong with the line.
+type Warnings = Vec<(usize, String)>;
+
 impl<'a> Gemtext {
     /// Creates a new [`Gemtext`] document from the given string.
     ///
+    /// A byte order mark at the very start of `input`, which some editors add, is skipped so it
+    /// doesn't end up in the first line. Blank lines are kept wherever they are, leading ones
+    /// included, as [`Element::Blank`].
+    ///
     /// # Examples
     ///
     /// ```
@@ -46,7 +250,7 @@ impl<'a> Gemtext {
     /// let expected_result = [
     ///                         gemtext::Element::Heading(String::from(" Example gemtext header")),
     ///                         gemtext::Element::Text(String::from("I'm a paragraph!")),
-    ///                         gemtext::Element::Link(String::from("gemini://gemini.circumlunar.space/"), String::from("gemini homepage link"))
+    ///                         gemtext::Element::Link(String::from("gemini://gemini.circumlunar.space/"), Some(String::from("gemini homepage link")))
     ///                       ];
     /// for (real, expected) in parsed_doc.elements.iter().zip(expected_result.iter()) {
     ///     assert_eq!(real, expected);
@@ -57,65 +261,204 @@ impl<'a> Gemtext {
     ///
     /// Will return an [`Error::GemtextFormat`] if there was a problem with parsing the document.
     pub fn new(input: &'a str) -> Result<Gemtext, Error> {
+        Self::parse_with(input, &ParseOptions::default())
+    }
+
+    /// Creates a new [`Gemtext`] document from the given string, parsing it as `options` says.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Element, Gemtext, ParseOptions};
+    ///
+    /// let options = ParseOptions { lenient: true, ..ParseOptions::default() };
+    /// let doc = Gemtext::parse_with("=>", &options).unwrap();
+    /// assert_eq!(doc.elements[0], Element::Text(String::from("=>")));
+    /// assert!(Gemtext::parse_with("=>", &ParseOptions::default()).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::GemtextFormat`] if there was a problem with parsing the document,
+    /// unless `options` is lenient.
+    pub fn parse_with(input: &'a str, options: &ParseOptions) -> Result<Gemtext, Error> {
+        let (elements, warnings) = Self::parse_spans(input, options);
+
+        match warnings.into_iter().next() {
+            Some((_line, warning)) if !options.lenient => Err(Error::GemtextFormat(warning)),
+            _ => Ok(Gemtext {
+                elements: elements.into_iter().map(|(element, _source)| element).collect(),
+            }),
+        }
+    }
+
+    /// Creates a new [`Gemtext`] document from the given string, recovering from malformed lines
+    /// instead of failing.
+    ///
+    /// Malformed lines are kept as [`Element::Text`] holding the line as it was written. Along
+    /// with the document, a list of warnings is returned holding the line number (starting at 1)
+    /// and a description of each problem that was recovered from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{self, Gemtext};
+    ///
+    /// let (parsed_doc, warnings) = Gemtext::parse_lenient("# Links\n=>\n");
+    /// assert_eq!(parsed_doc.elements[1], gemtext::Element::Text(String::from("=>")));
+    /// assert_eq!(warnings[0].0, 2);
+    /// ```
+    #[must_use]
+    pub fn parse_lenient(input: &'a str) -> (Gemtext, Vec<(usize, String)>) {
+        let options = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        let (elements, warnings) = Self::parse_spans(input, &options);
+        let elements = elements.into_iter().map(|(element, _source)| element).collect();
+
+        (Gemtext { elements }, warnings)
+    }
+
+    /// Parses the given string into its elements, pairing each with the part of `input` it was
+    /// parsed from.
+    ///
+    /// Parsing is lenient, in the same way as [`Gemtext::parse_lenient`]. The source of an
+    /// element that spans several lines, such as a list or preformatted block, covers all of
+    /// them including the line breaks between them, but not the final line break.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Element, Gemtext};
+    ///
+    /// let elements = Gemtext::parse_with_source("=>  gemini://example.org/  Example\n* a\n* b\n");
+    /// assert_eq!(elements[0].1, "=>  gemini://example.org/  Example");
+    /// assert_eq!(elements[1].1, "* a\n* b");
+    /// assert!(matches!(elements[1].0, Element::UnorderedList(_)));
+    /// ```
+    #[must_use]
+    pub fn parse_with_source(input: &'a str) -> Vec<(Element, &'a str)> {
+        let options = ParseOptions {
+            lenient: true,
+            ..ParseOptions::default()
+        };
+        Self::parse_spans(input, &options).0
+    }
+
+    fn parse_spans(
+        input: &'a str,
+        options: &ParseOptions,
+    ) -> (Vec<(Element, &'a str)>, Warnings) {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::debug_span!("parse_gemtext", bytes = input.len()).entered();
+
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
         let mut elements = Vec::with_capacity(input.lines().count());
+        let mut warnings = Vec::new();
+
+        // Every line is a slice of `input`, so where it starts can be worked out from its address.
+        let offset = |line: &str| line.as_ptr() as usize - input.as_ptr() as usize;
+        let source = |first: &str, last: &str| &input[offset(first)..offset(last) + last.len()];
+
+        let heading = |text: &str| {
+            if options.trim_headings {
+                text.trim().to_string()
+            } else {
+                text.to_string()
+            }
+        };
 
         // we have to de-sugar what would be a for loop into a while loop
         // because of how we parse 
         let mut lines = input.lines().enumerate().peekable();
         while let Some((index, line)) = lines.next() {
-            if let Some(line) = line.strip_prefix("=>") {
-                let text = line.trim_start();
+            let mut last_line = line;
+
+            let element = if line.trim().is_empty() && options.blank_lines {
+                Element::Blank
+            } else if let Some(link) = line.strip_prefix("=>") {
+                let text = link.trim_start();
                 if text.is_empty() {
                     // invalid link has no value.
-                    return Err(Error::GemtextFormat(format!(
-                        "Invalid link format, there must be \
-                        something after =>. Line #{}: {}",
+                    warnings.push((
                         index + 1,
-                        line.trim()
-                    )));
+                        format!(
+                            "Invalid link format, there must be \
+                            something after =>. Line #{}: {}",
+                            index + 1,
+                            link.trim()
+                        ),
+                    ));
+                    elements.push((Element::Text(line.to_string()), line));
+                    continue;
                 }
 
                 let (url, text) = if let Some(index) = text.find(char::is_whitespace) {
                     // get rid of the first space character, if there's more space then its part of
                     // how the human readable text is formatted.
                     let split = text.split_at(index+1);
-                    (split.0.trim(), split.1.trim())
+                    (split.0.trim(), Some(split.1.trim()).filter(|text| !text.is_empty()))
                 } else {
-                    (text, text)
+                    (text, None)
                 };
 
-                elements.push(Element::Link(url.to_string(), text.to_string()));
+                Element::Link(url.to_string(), text.map(str::to_string))
             } else if let Some(line) = line.strip_prefix("###") {
-                elements.push(Element::Subsubheading(line.trim().to_string()));
+                Element::Subsubheading(heading(line))
             } else if let Some(line) = line.strip_prefix("##") {
-                elements.push(Element::Subheading(line.trim().to_string()));
+                Element::Subheading(heading(line))
             } else if let Some(line) = line.strip_prefix('#') {
-                elements.push(Element::Heading(line.trim().to_string()));
+                Element::Heading(heading(line))
             } else if let Some(line) = line.strip_prefix('*') {
                 let mut list = Vec::new();
 
                 list.push(line.trim().to_string());
 
                 while let Some((_idx, line)) = lines.peek() {
-                    if let Some(line) = line.strip_prefix('*') {
-                        list.push(line.trim().to_string());
+                    if let Some(item) = line.strip_prefix('*') {
+                        list.push(item.trim().to_string());
+                        last_line = line;
                         lines.next();
                     } else {
                         break;
                     }
                 }
 
-                elements.push(Element::UnorderedList(list));
+                Element::UnorderedList(list)
             } else if let Some(line) = line.strip_prefix('>') {
-                elements.push(Element::BlockQuote(line.trim().to_string()));
+                let mut quote = line.to_string();
+
+                while let Some((_idx, line)) = lines.peek().filter(|_| options.coalesce_quotes) {
+                    if let Some(next) = line.strip_prefix('>') {
+                        quote += "\n";
+                        quote += next;
+                        last_line = line;
+                        lines.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                Element::BlockQuote(quote)
             } else if let Some(line) = line.strip_prefix("```") {
                 let alt_text = line.to_string();
-                let mut preformatted_block = String::new();
+                // The block is sliced straight out of the input rather than rebuilt from its
+                // lines, so whitespace and line endings come through untouched.
+                let mut block_range: Option<(usize, usize)> = None;
 
                 while let Some((_idx, line)) = lines.peek() {
-                    if let None = line.strip_prefix("```") {
-                        preformatted_block += line;
-                        preformatted_block += "\n";
+                    last_line = line;
+                    if !line.starts_with("```") {
+                        let start = offset(line);
+                        let mut end = start + line.len();
+                        // Take the line's own ending along with it, whichever one it used.
+                        if input[end..].starts_with("\r\n") {
+                            end += 2;
+                        } else if input[end..].starts_with('\n') {
+                            end += 1;
+                        }
+                        block_range = Some((block_range.map_or(start, |(start, _)| start), end));
                         lines.next();
                     }
                     else {
@@ -125,12 +468,428 @@ impl<'a> Gemtext {
                     }
                 }
 
-                elements.push(Element::Preformatted(alt_text, preformatted_block));
+                let mut preformatted_block = block_range
+                    .map_or_else(String::new, |(start, end)| input[start..end].to_string());
+                if !preformatted_block.is_empty() && !preformatted_block.ends_with('\n') {
+                    preformatted_block.push('\n');
+                }
+
+                Element::Preformatted(alt_text, preformatted_block)
             } else {
-                elements.push(Element::Text(line.to_string()));
+                Element::Text(line.to_string())
+            };
+
+            elements.push((element, source(line, last_line)));
+        }
+
+        (elements, warnings)
+    }
+}
+
+impl Gemtext {
+    /// Returns the number of elements in the document. A list counts as one element however
+    /// many items it has, as does a preformatted block, and every blank line is an element.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.elements.len()
+    }
+
+    /// Returns whether the document has no elements at all.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// assert!(Gemtext::new("").unwrap().is_empty());
+    /// assert!(!Gemtext::new("text").unwrap().is_empty());
+    /// ```
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.elements.is_empty()
+    }
+
+    /// Replaces every element of the document with the result of calling `f` on it, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Element, Gemtext};
+    ///
+    /// let mut doc = Gemtext::new("# Welcome\ntext").unwrap();
+    /// doc.map_elements(|element| match element {
+    ///     Element::Heading(text) => Element::Heading(text.to_uppercase()),
+    ///     element => element,
+    /// });
+    /// assert_eq!(doc.elements[0], Element::Heading(String::from(" WELCOME")));
+    /// ```
+    pub fn map_elements(&mut self, f: impl FnMut(Element) -> Element) {
+        self.elements = std::mem::take(&mut self.elements)
+            .into_iter()
+            .map(f)
+            .collect();
+    }
+
+    /// Returns an iterator over the document's links as `(url, text)` pairs. Links without
+    /// text of their own have their URL as the text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("=> gemini://example.org/ Example\ntext").unwrap();
+    /// assert_eq!(doc.links().collect::<Vec<_>>(), [("gemini://example.org/", "Example")]);
+    ///
+    /// let doc = Gemtext::new("=> gemini://example.org/").unwrap();
+    /// assert_eq!(doc.links().collect::<Vec<_>>(), [("gemini://example.org/", "gemini://example.org/")]);
+    /// ```
+    pub fn links(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Link(url, text) => Some((url.as_str(), text.as_deref().unwrap_or(url))),
+            _ => None,
+        })
+    }
+
+    /// Returns the document's links to gemini pages, resolved against `base`, the URL the
+    /// document was retrieved from, ready to be added to a crawler's queue.
+    ///
+    /// Each URL is normalized as [`super::urls::normalize_url`] describes, and only the first of
+    /// any duplicates is kept. Links to other schemes, and links that can't be resolved, are left
+    /// out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new(
+    ///     "=> /next\n\
+    ///      => gemini://EXAMPLE.org:1965/next#top\n\
+    ///      => ../other\n\
+    ///      => https://example.org/",
+    /// )
+    /// .unwrap();
+    /// let links: Vec<String> = doc
+    ///     .crawl_links("gemini://example.org/dir/page")
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .map(String::from)
+    ///     .collect();
+    /// assert_eq!(links, ["gemini://example.org/next", "gemini://example.org/other"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::UrlParse`] if `base` isn't a valid URL.
+    pub fn crawl_links(&self, base: &str) -> Result<Vec<url::Url>, Error> {
+        let base = url::Url::parse(base)?;
+        let mut seen = HashSet::new();
+
+        Ok(self
+            .links()
+            .filter_map(|(url, _text)| base.join(url).ok())
+            .filter(|url| url.scheme() == "gemini")
+            .map(super::urls::normalize)
+            .filter(|url| seen.insert(url.clone()))
+            .collect())
+    }
+
+    /// Returns an iterator over the document's headings as `(level, text)` pairs, where the
+    /// level is `1` for `#`, `2` for `##`, and `3` for `###`, and the text is trimmed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Title\ntext\n## Section").unwrap();
+    /// assert_eq!(doc.headings().collect::<Vec<_>>(), [(1, "Title"), (2, "Section")]);
+    /// ```
+    pub fn headings(&self) -> impl Iterator<Item = (u8, &str)> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Heading(text) => Some((1, text.trim())),
+            Element::Subheading(text) => Some((2, text.trim())),
+            Element::Subsubheading(text) => Some((3, text.trim())),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the document's preformatted blocks as `(alt_text, block)`
+    /// pairs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("```rust\nfn main() {}\n```").unwrap();
+    /// assert_eq!(doc.preformatted_blocks().collect::<Vec<_>>(), [("rust", "fn main() {}\n")]);
+    /// ```
+    pub fn preformatted_blocks(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.elements.iter().filter_map(|element| match element {
+            Element::Preformatted(alt_text, block) => Some((alt_text.as_str(), block.as_str())),
+            _ => None,
+        })
+    }
+
+    /// Returns an iterator over the items of every list in the document, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("* one\ntext\n* two").unwrap();
+    /// assert_eq!(doc.list_items().collect::<Vec<_>>(), ["one", "two"]);
+    /// ```
+    pub fn list_items(&self) -> impl Iterator<Item = &str> {
+        self.elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::UnorderedList(items) => Some(items),
+                _ => None,
+            })
+            .flatten()
+            .map(String::as_str)
+    }
+
+    /// Returns the number of links in the document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Links\n=> gemini://example.org/\n=> gemini://example.com/").unwrap();
+    /// assert_eq!(doc.link_count(), 2);
+    /// assert_eq!(doc.heading_count(), 1);
+    /// ```
+    #[must_use]
+    pub fn link_count(&self) -> usize {
+        self.links().count()
+    }
+
+    /// Returns the number of headings of any level in the document.
+    #[must_use]
+    pub fn heading_count(&self) -> usize {
+        self.headings().count()
+    }
+
+    /// Appends the elements of `other` to the end of the document.
+    ///
+    /// If the document ends with a list and `other` starts with one, a blank line is put between
+    /// them so they stay two lists when the document is written out and parsed again, instead of
+    /// being read back as one. Preformatted blocks are self-contained, so they need no such care.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let mut doc = Gemtext::new("# Fruit\n* apple").unwrap();
+    /// doc.append(Gemtext::new("* carrot\n=> / Home").unwrap());
+    /// assert_eq!(doc.to_string(), "# Fruit\n* apple\n\n* carrot\n=> / Home\n");
+    /// ```
+    pub fn append(&mut self, other: Gemtext) {
+        let lists_meet = matches!(self.elements.last(), Some(Element::UnorderedList(_)))
+            && matches!(other.elements.first(), Some(Element::UnorderedList(_)));
+        if lists_meet {
+            self.elements.push(Element::Blank);
+        }
+        self.elements.extend(other.elements);
+    }
+
+    /// Joins documents into one, in order, the way [`Gemtext::append`] does.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let header = Gemtext::new("# My capsule").unwrap();
+    /// let body = Gemtext::new("Hello!").unwrap();
+    /// let footer = Gemtext::new("=> / Home").unwrap();
+    ///
+    /// let page = Gemtext::concat([header, body, footer]);
+    /// assert_eq!(page, Gemtext::new("# My capsule\nHello!\n=> / Home").unwrap());
+    /// ```
+    #[must_use]
+    pub fn concat(docs: impl IntoIterator<Item = Gemtext>) -> Gemtext {
+        let mut joined = Gemtext {
+            elements: Vec::new(),
+        };
+        for doc in docs {
+            joined.append(doc);
+        }
+
+        joined
+    }
+}
+
+impl Extend<Element> for Gemtext {
+    /// Adds the elements to the end of the document as they are.
+    fn extend<I: IntoIterator<Item = Element>>(&mut self, elements: I) {
+        self.elements.extend(elements);
+    }
+}
+
+impl FromIterator<Element> for Gemtext {
+    /// Makes a document out of the elements, in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Element, Gemtext};
+    ///
+    /// let doc: Gemtext = Gemtext::new("# Title\ntext\n## Section")
+    ///     .unwrap()
+    ///     .into_iter()
+    ///     .filter(|element| !matches!(element, Element::Text(_)))
+    ///     .collect();
+    /// assert_eq!(doc.heading_count(), 2);
+    /// assert_eq!(doc.elements.len(), 2);
+    /// ```
+    fn from_iter<I: IntoIterator<Item = Element>>(elements: I) -> Gemtext {
+        Gemtext {
+            elements: elements.into_iter().collect(),
+        }
+    }
+}
+
+impl FromStr for Gemtext {
+    type Err = Error;
+
+    /// Parses a gemtext document, see [`Gemtext::new`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc: Gemtext = "# Heading\nparagraph".parse().unwrap();
+    /// assert_eq!(doc.elements.len(), 2);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::GemtextFormat`] if there was a problem with parsing the document.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Gemtext::new(s)
+    }
+}
+
+impl std::fmt::Display for Element {
+    /// Formats the element as the gemtext line it's written as, or lines for lists, coalesced
+    /// quotes, and preformatted blocks, without a final line break.
+    ///
+    /// A space is put after the `#` of headings and the `>` of quotes if their text doesn't
+    /// already start with one, and a link without text is written with just the URL.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::Element;
+    ///
+    /// assert_eq!(Element::heading("Title").to_string(), "# Title");
+    /// assert_eq!(Element::list(["one", "two"]).to_string(), "* one\n* two");
+    /// assert_eq!(Element::preformatted("", "=^.^=").to_string(), "```\n=^.^=\n```");
+    /// ```
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Headings and quotes keep the whitespace after their prefix when parsed, so only add
+        // a separator if there isn't one.
+        let spaced = |text: &str| {
+            if text.starts_with(char::is_whitespace) || text.is_empty() {
+                String::from(text)
+            } else {
+                format!(" {}", text)
+            }
+        };
+
+        match self {
+            Element::Text(text) => write!(f, "{}", text),
+            Element::Blank => Ok(()),
+            Element::Link(url, Some(text)) => write!(f, "=> {} {}", url, text),
+            Element::Link(url, None) => write!(f, "=> {}", url),
+            Element::Heading(text) => write!(f, "#{}", spaced(text)),
+            Element::Subheading(text) => write!(f, "##{}", spaced(text)),
+            Element::Subsubheading(text) => write!(f, "###{}", spaced(text)),
+            Element::UnorderedList(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "* {}", item)?;
+                }
+                Ok(())
+            }
+            Element::BlockQuote(text) => {
+                // Quotes coalesced while parsing span several lines.
+                for (index, line) in text.split('\n').enumerate() {
+                    if index > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, ">{}", spaced(line))?;
+                }
+                Ok(())
+            }
+            Element::Preformatted(alt_text, block) => {
+                writeln!(f, "```{}", alt_text)?;
+                write!(f, "{}", block)?;
+                if !block.is_empty() && !block.ends_with('\n') {
+                    writeln!(f)?;
+                }
+                write!(f, "```")
             }
         }
+    }
+}
 
-        Ok(Gemtext { elements })
+impl std::fmt::Display for Gemtext {
+    /// Formats the document as gemtext source, which parses back into the same elements. Each
+    /// element is written as its own [`Display`](std::fmt::Display) implementation does, followed
+    /// by a line break.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for element in &self.elements {
+            // An empty list has no lines at all, not even an empty one.
+            if matches!(element, Element::UnorderedList(items) if items.is_empty()) {
+                continue;
+            }
+            writeln!(f, "{}", element)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl IntoIterator for Gemtext {
+    type Item = Element;
+    type IntoIter = std::vec::IntoIter<Element>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Gemtext {
+    type Item = &'a Element;
+    type IntoIter = std::slice::Iter<'a, Element>;
+
+    /// Iterates over the document's elements in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Element, Gemtext};
+    ///
+    /// let doc = Gemtext::new("# Heading\n=> gemini://example.org/ Example").unwrap();
+    /// for element in &doc {
+    ///     if let Element::Link(url, _text) = element {
+    ///         assert_eq!(url, "gemini://example.org/");
+    ///     }
+    /// }
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        self.elements.iter()
     }
 }
@@ -0,0 +1,102 @@
+use super::{Element, Gemtext};
+
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const ITALIC: &str = "\x1b[3m";
+const UNDERLINE: &str = "\x1b[4m";
+const RESET: &str = "\x1b[0m";
+
+impl Gemtext {
+    /// Renders the document as text styled with ANSI escape codes for display in a terminal
+    /// `width` columns wide.
+    ///
+    /// Headings are bold, links are numbered in order with dim numbers, block quotes are
+    /// indented, and list items are bulleted. Everything except preformatted blocks is word
+    /// wrapped to `width`, preformatted blocks are passed through verbatim so their layout is
+    /// kept. Words longer than `width` are put on their own line rather than broken up.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("one two three four").unwrap();
+    /// assert_eq!(doc.to_ansi(9), "one two\nthree\nfour\n");
+    /// ```
+    #[must_use]
+    pub fn to_ansi(&self, width: usize) -> String {
+        let mut output = String::new();
+        let mut link_count = 0;
+
+        for element in &self.elements {
+            match element {
+                Element::Text(text) => push_wrapped(&mut output, text, width, "", "", ""),
+                Element::Blank => output += "\n",
+                Element::Link(url, text) => {
+                    let text = text.as_deref().unwrap_or(url);
+                    link_count += 1;
+                    let number = format!("[{}]", link_count);
+                    let prefix = format!("{}{}{} ", DIM, number, RESET);
+                    let indent = " ".repeat(number.chars().count() + 1);
+                    push_wrapped(&mut output, text, width, &prefix, &indent, UNDERLINE);
+                }
+                Element::Heading(text) => {
+                    let style = format!("{}{}", BOLD, UNDERLINE);
+                    push_wrapped(&mut output, text, width, "", "", &style);
+                }
+                Element::Subheading(text) | Element::Subsubheading(text) => {
+                    push_wrapped(&mut output, text, width, "", "", BOLD);
+                }
+                Element::UnorderedList(items) => {
+                    for item in items {
+                        push_wrapped(&mut output, item, width, "• ", "  ", "");
+                    }
+                }
+                Element::BlockQuote(text) => {
+                    push_wrapped(&mut output, text, width, "    ", "    ", ITALIC);
+                }
+                Element::Preformatted(_alt_text, block) => output += block,
+            }
+        }
+
+        output
+    }
+}
+
+/// Word wraps `text` to `width` columns and appends it to `output`, one line at a time.
+///
+/// The first line starts with `prefix` and the rest with `indent`, which must have the same
+/// visible width. The wrapped text itself is wrapped in `style` if it isn't empty.
+fn push_wrapped(
+    output: &mut String,
+    text: &str,
+    width: usize,
+    prefix: &str,
+    indent: &str,
+    style: &str,
+) {
+    let available = width.saturating_sub(indent.chars().count()).max(1);
+
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > available {
+            lines.push(std::mem::take(&mut line));
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line += word;
+    }
+    lines.push(line);
+
+    for (index, line) in lines.iter().enumerate() {
+        *output += if index == 0 { prefix } else { indent };
+        if style.is_empty() || line.is_empty() {
+            *output += line;
+        } else {
+            *output += &format!("{}{}{}", style, line, RESET);
+        }
+        *output += "\n";
+    }
+}
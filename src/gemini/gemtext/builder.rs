@@ -0,0 +1,116 @@
+use super::{Element, Gemtext};
+
+/// Builds a [`Gemtext`] document one element at a time, created with [`Gemtext::builder`].
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::Gemtext;
+///
+/// let doc = Gemtext::builder()
+///     .heading("My feed")
+///     .link("gemini://example.org/first.gmi", "First post")
+///     .list(["one", "two"])
+///     .build();
+/// assert_eq!(
+///     doc.to_string(),
+///     "# My feed\n=> gemini://example.org/first.gmi First post\n* one\n* two\n"
+/// );
+/// ```
+#[derive(Debug, Default)]
+pub struct GemtextBuilder {
+    elements: Vec<Element>,
+}
+
+impl GemtextBuilder {
+    /// Adds an element of any kind.
+    #[must_use]
+    pub fn element(mut self, element: Element) -> GemtextBuilder {
+        self.elements.push(element);
+        self
+    }
+
+    /// Adds a line of text.
+    #[must_use]
+    pub fn text(self, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::text(text))
+    }
+
+    /// Adds a blank line.
+    #[must_use]
+    pub fn blank(self) -> GemtextBuilder {
+        self.element(Element::Blank)
+    }
+
+    /// Adds a link to `url` shown as `text`.
+    #[must_use]
+    pub fn link(self, url: impl Into<String>, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::link(url, text))
+    }
+
+    /// Adds a link to `url` without any text, readers show the URL itself.
+    #[must_use]
+    pub fn bare_link(self, url: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::bare_link(url))
+    }
+
+    /// Adds a top level heading.
+    #[must_use]
+    pub fn heading(self, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::heading(text))
+    }
+
+    /// Adds a second level heading.
+    #[must_use]
+    pub fn subheading(self, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::subheading(text))
+    }
+
+    /// Adds a third level heading.
+    #[must_use]
+    pub fn subsubheading(self, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::subsubheading(text))
+    }
+
+    /// Adds a list with the given items.
+    #[must_use]
+    pub fn list<I>(self, items: I) -> GemtextBuilder
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.element(Element::list(items))
+    }
+
+    /// Adds a block quote.
+    #[must_use]
+    pub fn quote(self, text: impl Into<String>) -> GemtextBuilder {
+        self.element(Element::quote(text))
+    }
+
+    /// Adds a preformatted block with the given alt text.
+    #[must_use]
+    pub fn preformatted(
+        self,
+        alt_text: impl Into<String>,
+        block: impl Into<String>,
+    ) -> GemtextBuilder {
+        self.element(Element::preformatted(alt_text, block))
+    }
+
+    /// Returns the built document.
+    #[must_use]
+    pub fn build(self) -> Gemtext {
+        Gemtext {
+            elements: self.elements,
+        }
+    }
+}
+
+impl Gemtext {
+    /// Returns a builder to construct a document in code.
+    #[must_use]
+    pub fn builder() -> GemtextBuilder {
+        GemtextBuilder::default()
+    }
+}
@@ -0,0 +1,191 @@
+use std::mem;
+
+use super::{Element, Gemtext};
+
+/// Represents how an element differs between two versions of a document, see [`Gemtext::diff`].
+#[derive(Debug, PartialEq)]
+pub enum Change<'a> {
+    /// The element is in both versions.
+    Unchanged(&'a Element),
+    /// The element is only in the new version.
+    Added(&'a Element),
+    /// The element is only in the old version.
+    Removed(&'a Element),
+    /// The element was edited in place, the first member is the old element and the second is
+    /// the new one. Both are the same kind of element, such as two links.
+    Changed(&'a Element, &'a Element),
+}
+
+impl Gemtext {
+    /// Compares the document's elements to those of `new`, a later version of it, and returns
+    /// the changes that turn one into the other, in document order.
+    ///
+    /// The elements the two versions have in common are found with a longest common
+    /// subsequence, everything else was added or removed. Where elements were removed and others
+    /// added in the same place, each removed element is paired with the added element in the same
+    /// position of the run, and the pair is reported as [`Change::Changed`] if both are the same
+    /// kind of element.
+    ///
+    /// Once the unchanged start and end of the document are set aside, this takes time proportional
+    /// to the product of the number of elements left in each version, but only linear memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Change, Element, Gemtext};
+    ///
+    /// let old = Gemtext::new("# Posts\n=> /one.gmi First post\nBye").unwrap();
+    /// let new = Gemtext::new("# Posts\n=> /two.gmi Second post\n=> /one.gmi First post").unwrap();
+    ///
+    /// let changes: Vec<_> = old
+    ///     .diff(&new)
+    ///     .into_iter()
+    ///     .filter(|change| !matches!(change, Change::Unchanged(_)))
+    ///     .collect();
+    /// assert_eq!(
+    ///     changes,
+    ///     [
+    ///         Change::Added(&new.elements[1]),
+    ///         Change::Removed(&Element::Text(String::from("Bye"))),
+    ///     ]
+    /// );
+    /// ```
+    #[must_use]
+    pub fn diff<'a>(&'a self, new: &'a Gemtext) -> Vec<Change<'a>> {
+        let (old, new) = (&self.elements, &new.elements);
+
+        // Only the elements between the unchanged start and end need aligning.
+        let start = old
+            .iter()
+            .zip(new)
+            .take_while(|(old, new)| old == new)
+            .count();
+        let end = old[start..]
+            .iter()
+            .rev()
+            .zip(new[start..].iter().rev())
+            .take_while(|(old, new)| old == new)
+            .count();
+        let mut edits = vec![Edit::Keep; start];
+        align(
+            &old[start..old.len() - end],
+            &new[start..new.len() - end],
+            &mut edits,
+        );
+        edits.resize(edits.len() + end, Edit::Keep);
+
+        let mut changes = Vec::with_capacity(old.len().max(new.len()));
+        let (mut removed, mut added) = (Vec::new(), Vec::new());
+        let (mut i, mut j) = (0, 0);
+        for edit in edits {
+            match edit {
+                Edit::Keep => {
+                    pair(&mut changes, &mut removed, &mut added);
+                    changes.push(Change::Unchanged(&old[i]));
+                    i += 1;
+                    j += 1;
+                }
+                Edit::Remove => {
+                    removed.push(&old[i]);
+                    i += 1;
+                }
+                Edit::Add => {
+                    added.push(&new[j]);
+                    j += 1;
+                }
+            }
+        }
+        pair(&mut changes, &mut removed, &mut added);
+
+        changes
+    }
+}
+
+/// A step in turning one run of elements into another.
+#[derive(Clone, Copy)]
+enum Edit {
+    Keep,
+    Remove,
+    Add,
+}
+
+/// Appends the edits that turn `old` into `new` while keeping a longest common subsequence of
+/// them.
+///
+/// This is Hirschberg's algorithm: `old` is split in half, and `new` where the subsequence
+/// crosses that split, so only a row of the usual table is kept at a time. It takes time
+/// proportional to `old.len() * new.len()` but only linear memory.
+fn align(old: &[Element], new: &[Element], edits: &mut Vec<Edit>) {
+    match old {
+        [] => edits.resize(edits.len() + new.len(), Edit::Add),
+        [element] => match new.iter().position(|new| new == element) {
+            Some(j) => {
+                edits.resize(edits.len() + j, Edit::Add);
+                edits.push(Edit::Keep);
+                edits.resize(edits.len() + new.len() - j - 1, Edit::Add);
+            }
+            None => {
+                edits.push(Edit::Remove);
+                edits.resize(edits.len() + new.len(), Edit::Add);
+            }
+        },
+        _ if new.is_empty() => edits.resize(edits.len() + old.len(), Edit::Remove),
+        _ => {
+            let (front, back) = old.split_at(old.len() / 2);
+            let before = lengths(front.iter(), new.iter());
+            let after = lengths(back.iter().rev(), new.iter().rev());
+            let split = (0..=new.len())
+                .max_by_key(|&j| before[j] + after[new.len() - j])
+                .unwrap_or_default();
+            align(front, &new[..split], edits);
+            align(back, &new[split..], edits);
+        }
+    }
+}
+
+/// Returns the length of the longest common subsequence of `old` and every prefix of `new`,
+/// shortest first.
+fn lengths<'a>(
+    old: impl Iterator<Item = &'a Element>,
+    new: impl Iterator<Item = &'a Element> + Clone,
+) -> Vec<usize> {
+    let mut row = vec![0; new.clone().count() + 1];
+    for old in old {
+        let mut diagonal = 0;
+        for (j, new) in new.clone().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if old == new {
+                diagonal + 1
+            } else {
+                above.max(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row
+}
+
+/// Moves a run of `removed` and `added` elements into `changes`, pairing them up by position
+/// into [`Change::Changed`] where they're the same kind of element.
+fn pair<'a>(
+    changes: &mut Vec<Change<'a>>,
+    removed: &mut Vec<&'a Element>,
+    added: &mut Vec<&'a Element>,
+) {
+    let mut removed = removed.drain(..);
+    let mut added = added.drain(..);
+    loop {
+        match (removed.next(), added.next()) {
+            (Some(old), Some(new)) if mem::discriminant(old) == mem::discriminant(new) => {
+                changes.push(Change::Changed(old, new));
+            }
+            (Some(old), Some(new)) => {
+                changes.push(Change::Removed(old));
+                changes.push(Change::Added(new));
+            }
+            (Some(old), None) => changes.push(Change::Removed(old)),
+            (None, Some(new)) => changes.push(Change::Added(new)),
+            (None, None) => break,
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use std::io::{self, Write};
+
+use super::outline::slugify;
+use super::{Element, Gemtext};
+
+/// Represents the choices [`Gemtext::to_html_with`] offers in how a document is rendered.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::gemtext::{Gemtext, HtmlOptions};
+///
+/// let options = HtmlOptions {
+///     line_breaks: true,
+///     ..HtmlOptions::default()
+/// };
+/// let doc = Gemtext::new("one\n\n\ntwo").unwrap();
+/// assert_eq!(doc.to_html_with(&options), "<p>one</p>\n<br>\n<p>two</p>\n");
+/// assert_eq!(doc.to_html(), "<p>one</p>\n<p>two</p>\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct HtmlOptions {
+    /// Render each run of blank lines as a single `<br>`, so the spacing the author left between
+    /// lines shows. Off by default, blank lines are dropped and spacing is left to the
+    /// stylesheet.
+    pub line_breaks: bool,
+}
+
+impl Gemtext {
+    /// Renders the document as an HTML fragment.
+    ///
+    /// This is a thin wrapper over [`Gemtext::write_html`], see it for how elements are
+    /// rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Hello\n=> gemini://example.org/ <Example>").unwrap();
+    /// assert_eq!(
+    ///     doc.to_html(),
+    ///     "<h1 id=\"hello\">Hello</h1>\n\
+    ///      <p><a href=\"gemini://example.org/\">&lt;Example&gt;</a></p>\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.to_html_with(&HtmlOptions::default())
+    }
+
+    /// Renders the document as an HTML fragment the way `options` asks for, see
+    /// [`HtmlOptions`].
+    #[must_use]
+    pub fn to_html_with(&self, options: &HtmlOptions) -> String {
+        let mut output = Vec::new();
+        self.write_html_with(&mut output, options)
+            .expect("Writing to a Vec can't fail");
+        String::from_utf8(output).expect("HTML is only ever written as UTF-8")
+    }
+
+    /// Writes the document to `writer` as an HTML fragment, one element at a time.
+    ///
+    /// Text and links become paragraphs, blank lines are dropped, headings get an `id` from
+    /// [`Gemtext::outline`] so they can be linked to, lists become `<ul>`, block quotes become
+    /// `<blockquote>`, and preformatted blocks become `<pre>` labelled with their alt text. Blocks
+    /// whose alt text names an [`Element::language`] wrap their text in a
+    /// `<code class="language-...">` for syntax highlighters to pick up. All text is escaped.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`io::Error`] if writing to `writer` failed.
+    pub fn write_html<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.write_html_with(writer, &HtmlOptions::default())
+    }
+
+    /// Writes the document to `writer` as an HTML fragment the way `options` asks for, see
+    /// [`Gemtext::write_html`] and [`HtmlOptions`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`io::Error`] if writing to `writer` failed.
+    pub fn write_html_with<W: Write>(
+        &self,
+        writer: &mut W,
+        options: &HtmlOptions,
+    ) -> io::Result<()> {
+        let mut slugs = self.outline().into_iter().map(|entry| entry.slug);
+        let mut after_blank = false;
+
+        for element in &self.elements {
+            if let Element::Blank = element {
+                // Only the first blank line of a run breaks, the rest would just stack up.
+                if options.line_breaks && !after_blank {
+                    writeln!(writer, "<br>")?;
+                }
+                after_blank = true;
+                continue;
+            }
+            after_blank = false;
+
+            let slug = match element {
+                Element::Heading(_) | Element::Subheading(_) | Element::Subsubheading(_) => {
+                    slugs.next()
+                }
+                _ => None,
+            };
+            write_element(element, slug.as_deref(), writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Element {
+    /// Renders the element on its own as HTML, the same way [`Gemtext::to_html`] renders it as
+    /// part of a document, so a document can be rendered a piece at a time.
+    ///
+    /// The `id` of a heading is made from its text alone. When rendering a whole document, ids
+    /// are made unique across its headings, so two headings with the same text get different
+    /// ids there.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::Element;
+    ///
+    /// let element = Element::Link(String::from("/a?b&c"), Some(String::from("Next")));
+    /// assert_eq!(element.to_html(), "<p><a href=\"/a?b&amp;c\">Next</a></p>\n");
+    /// ```
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        let slug = match self {
+            Element::Heading(text) | Element::Subheading(text) | Element::Subsubheading(text) => {
+                Some(slugify(text.trim()))
+            }
+            _ => None,
+        };
+
+        let mut output = Vec::new();
+        write_element(self, slug.as_deref(), &mut output).expect("Writing to a Vec can't fail");
+        String::from_utf8(output).expect("HTML is only ever written as UTF-8")
+    }
+}
+
+/// Writes a single element to `writer` as HTML, giving it `slug` as its id if it's a heading.
+fn write_element<W: Write>(
+    element: &Element,
+    slug: Option<&str>,
+    writer: &mut W,
+) -> io::Result<()> {
+    match element {
+        Element::Text(text) => writeln!(writer, "<p>{}</p>", escape(text))?,
+        // Spacing between paragraphs is left to the stylesheet.
+        Element::Blank => {}
+        Element::Link(url, text) => writeln!(
+            writer,
+            "<p><a href=\"{}\">{}</a></p>",
+            escape(url),
+            escape(text.as_deref().unwrap_or(url))
+        )?,
+        Element::Heading(text) | Element::Subheading(text) | Element::Subsubheading(text) => {
+            let level = match element {
+                Element::Heading(_) => 1,
+                Element::Subheading(_) => 2,
+                _ => 3,
+            };
+            writeln!(
+                writer,
+                "<h{level} id=\"{}\">{}</h{level}>",
+                escape(slug.unwrap_or_default()),
+                escape(text.trim()),
+            )?;
+        }
+        Element::UnorderedList(items) => {
+            writeln!(writer, "<ul>")?;
+            for item in items {
+                writeln!(writer, "<li>{}</li>", escape(item))?;
+            }
+            writeln!(writer, "</ul>")?;
+        }
+        Element::BlockQuote(text) => {
+            writeln!(writer, "<blockquote>{}</blockquote>", escape(text.trim()))?;
+        }
+        Element::Preformatted(_alt_text, block) => {
+            match element.alt_text() {
+                Some(alt_text) => write!(writer, "<pre aria-label=\"{}\">", escape(alt_text))?,
+                None => write!(writer, "<pre>")?,
+            }
+            // Highlighters look for the language as a class on a <code> inside the <pre>.
+            match element.language() {
+                Some(language) => writeln!(
+                    writer,
+                    "<code class=\"language-{}\">{}</code></pre>",
+                    escape(language),
+                    escape(block)
+                )?,
+                None => writeln!(writer, "{}</pre>", escape(block))?,
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Escapes the characters that are special in HTML text and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped += "&amp;",
+            '<' => escaped += "&lt;",
+            '>' => escaped += "&gt;",
+            '"' => escaped += "&quot;",
+            '\'' => escaped += "&#39;",
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
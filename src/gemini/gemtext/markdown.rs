@@ -0,0 +1,115 @@
+use std::io::{self, Write};
+
+use super::{Element, Gemtext};
+
+impl Gemtext {
+    /// Renders the document as CommonMark.
+    ///
+    /// This is a thin wrapper over [`Gemtext::write_markdown`], see it for how elements are
+    /// rendered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Hello\n=> gemini://example.org/ Example\n* one").unwrap();
+    /// assert_eq!(
+    ///     doc.to_markdown(),
+    ///     "# Hello\n\n[Example](<gemini://example.org/>)\n\n- one\n\n"
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        let mut output = Vec::new();
+        self.write_markdown(&mut output)
+            .expect("Writing to a Vec can't fail");
+        String::from_utf8(output).expect("Markdown is only ever written as UTF-8")
+    }
+
+    /// Writes the document to `writer` as CommonMark, one element at a time.
+    ///
+    /// Every line of text becomes its own paragraph, as gemtext lines aren't joined the way
+    /// markdown lines are, and blank lines are dropped. Preformatted blocks become fenced code
+    /// blocks with a fence longer than any run of backticks inside them, and the block's
+    /// [`Element::language`] as their info string. Characters markdown would treat as formatting
+    /// are escaped, and so are the characters that would end a link's URL early.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`io::Error`] if writing to `writer` failed.
+    pub fn write_markdown<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        for element in &self.elements {
+            match element {
+                Element::Blank => {}
+                Element::Text(text) => write!(writer, "{}\n\n", escape(text))?,
+                Element::Link(url, text) => write!(
+                    writer,
+                    "[{}](<{}>)\n\n",
+                    escape(text.as_deref().unwrap_or(url)),
+                    destination(url)
+                )?,
+                Element::Heading(text) => write!(writer, "# {}\n\n", escape(text.trim()))?,
+                Element::Subheading(text) => write!(writer, "## {}\n\n", escape(text.trim()))?,
+                Element::Subsubheading(text) => write!(writer, "### {}\n\n", escape(text.trim()))?,
+                Element::UnorderedList(items) => {
+                    for item in items {
+                        writeln!(writer, "- {}", escape(item))?;
+                    }
+                    writeln!(writer)?;
+                }
+                Element::BlockQuote(text) => write!(writer, "> {}\n\n", escape(text.trim()))?,
+                Element::Preformatted(_alt_text, block) => {
+                    let longest_run = block.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+                    let fence = "`".repeat(longest_run.max(2) + 1);
+                    let language = element.language().unwrap_or_default();
+                    write!(writer, "{}{}\n{}{}\n\n", fence, language, block, fence)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Percent-encodes the characters that would end a link destination written between `<` and `>`,
+/// or break the link across lines.
+fn destination(url: &str) -> String {
+    let mut encoded = String::with_capacity(url.len());
+    for c in url.chars() {
+        match c {
+            '<' => encoded.push_str("%3C"),
+            '>' => encoded.push_str("%3E"),
+            '\n' => encoded.push_str("%0A"),
+            '\r' => encoded.push_str("%0D"),
+            _ => encoded.push(c),
+        }
+    }
+
+    encoded
+}
+
+/// Escapes the characters markdown would treat as formatting, including what would start a list
+/// or setext heading at the beginning of the line.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let digits = text.chars().take_while(char::is_ascii_digit).count();
+    for (index, c) in text.chars().enumerate() {
+        let starts_block = match c {
+            '-' | '+' | '=' => index == 0,
+            '.' | ')' => index == digits && digits > 0,
+            _ => false,
+        };
+        if starts_block
+            || matches!(
+                c,
+                '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '#' | '|'
+            )
+        {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+
+    escaped
+}
@@ -0,0 +1,104 @@
+use std::collections::HashSet;
+
+use super::{Element, Gemtext};
+
+/// Represents a heading in a [`Gemtext`] document's outline.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct OutlineEntry {
+    /// How deep the heading is, `1` for `#`, `2` for `##`, and `3` for `###`.
+    pub level: u8,
+    /// The heading's text, trimmed.
+    pub text: String,
+    /// An identifier for the heading made from its text, unique within the document, suitable to
+    /// link to as `#slug`.
+    pub slug: String,
+}
+
+impl Gemtext {
+    /// Returns the text of the document's first top level heading, trimmed, which is
+    /// conventionally used as its title.
+    ///
+    /// Returns `None` if the document has no `#` heading, in which case the URL is usually shown
+    /// instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("## Not this\n#  My capsule \n# Later").unwrap();
+    /// assert_eq!(doc.title(), Some("My capsule"));
+    /// assert_eq!(Gemtext::new("text").unwrap().title(), None);
+    /// ```
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.elements.iter().find_map(|element| match element {
+            Element::Heading(text) => Some(text.trim()),
+            _ => None,
+        })
+    }
+
+    /// Returns every heading in the document in order, along with its level and a unique slug.
+    ///
+    /// Slugs are the heading's text lowercased, with runs of anything other than letters and
+    /// digits replaced by a single `-`. When several headings would share a slug, the later ones
+    /// are suffixed with `-1`, `-2`, and so on.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Getting started\n## Notes\n# Usage\n## Notes").unwrap();
+    /// let outline = doc.outline();
+    /// assert_eq!(outline[0].slug, "getting-started");
+    /// assert_eq!(outline[1].level, 2);
+    /// assert_eq!(outline[3].slug, "notes-1");
+    /// ```
+    #[must_use]
+    pub fn outline(&self) -> Vec<OutlineEntry> {
+        let mut used = HashSet::new();
+
+        self.elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Heading(text) => Some((1, text)),
+                Element::Subheading(text) => Some((2, text)),
+                Element::Subsubheading(text) => Some((3, text)),
+                _ => None,
+            })
+            .map(|(level, text)| {
+                let text = text.trim();
+                let base = slugify(text);
+                let mut slug = base.clone();
+                let mut suffix = 0;
+                while !used.insert(slug.clone()) {
+                    suffix += 1;
+                    slug = format!("{}-{}", base, suffix);
+                }
+
+                OutlineEntry {
+                    level,
+                    text: text.to_string(),
+                    slug,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Lowercases `text` and joins its runs of letters and digits with `-`.
+pub(super) fn slugify(text: &str) -> String {
+    let slug = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if slug.is_empty() {
+        String::from("section")
+    } else {
+        slug
+    }
+}
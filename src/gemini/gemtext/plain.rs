@@ -0,0 +1,100 @@
+use super::{Element, Gemtext};
+
+/// Represents the ways links can be presented when rendering a [`Gemtext`] as plain text.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LinkStyle {
+    /// Show the link's URL in parentheses after its text, `text (url)`.
+    Inline,
+    /// Show only the link's text.
+    Hidden,
+    /// Number each link after its text, `text [1]`, and list the URLs at the end of the document.
+    Footnote,
+}
+
+impl Gemtext {
+    /// Renders the document as plain text with all formatting stripped, showing links inline.
+    ///
+    /// This is the same as calling [`Gemtext::to_text`] with [`LinkStyle::Inline`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new("# Welcome\n=> gemini://example.org/ Example\n* one\n* two").unwrap();
+    /// assert_eq!(doc.to_plain(), "Welcome\nExample (gemini://example.org/)\n• one\n• two\n");
+    /// ```
+    #[must_use]
+    pub fn to_plain(&self) -> String {
+        self.to_text(LinkStyle::Inline)
+    }
+
+    /// Renders the document as plain text with all formatting stripped.
+    ///
+    /// Headings and block quotes become their bare text, list items are prefixed with a bullet,
+    /// preformatted blocks are passed through verbatim, and links are presented according to
+    /// `links`. A link whose text is its URL is only ever shown once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Gemtext, gemtext::LinkStyle};
+    ///
+    /// let doc = Gemtext::new("=> gemini://example.org/ Example").unwrap();
+    /// assert_eq!(doc.to_text(LinkStyle::Hidden), "Example\n");
+    /// assert_eq!(doc.to_text(LinkStyle::Footnote), "Example [1]\n\n[1] gemini://example.org/\n");
+    /// ```
+    #[must_use]
+    pub fn to_text(&self, links: LinkStyle) -> String {
+        let mut output = String::new();
+        let mut footnotes = Vec::new();
+
+        for element in &self.elements {
+            match element {
+                Element::Blank => output += "\n",
+                Element::Text(text) => {
+                    output += text;
+                    output += "\n";
+                }
+                Element::Link(url, text) => {
+                    let shown = text.as_deref().unwrap_or(url);
+                    match (links, text) {
+                        (LinkStyle::Inline, Some(text)) if text != url => {
+                            output += &format!("{} ({})", text, url);
+                        }
+                        (LinkStyle::Footnote, _) => {
+                            footnotes.push(url);
+                            output += &format!("{} [{}]", shown, footnotes.len());
+                        }
+                        _ => output += shown,
+                    }
+                    output += "\n";
+                }
+                Element::Heading(text)
+                | Element::Subheading(text)
+                | Element::Subsubheading(text)
+                | Element::BlockQuote(text) => {
+                    output += text.trim();
+                    output += "\n";
+                }
+                Element::UnorderedList(items) => {
+                    for item in items {
+                        output += "• ";
+                        output += item;
+                        output += "\n";
+                    }
+                }
+                Element::Preformatted(_alt_text, block) => output += block,
+            }
+        }
+
+        if !footnotes.is_empty() {
+            output += "\n";
+            for (index, url) in footnotes.iter().enumerate() {
+                output += &format!("[{}] {}\n", index + 1, url);
+            }
+        }
+
+        output
+    }
+}
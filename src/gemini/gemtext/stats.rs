@@ -0,0 +1,89 @@
+use super::{Element, Gemtext};
+use crate::gemini::urls;
+
+/// Represents counts of what a [`Gemtext`] document is made of, see [`Gemtext::stats`].
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub struct GemtextStats {
+    /// The number of links.
+    pub links: usize,
+    /// The number of links to the same host as the document.
+    pub internal_links: usize,
+    /// The number of links to anywhere else, `internal_links + external_links == links`.
+    pub external_links: usize,
+    /// The number of headings at each level, `headings[0]` counts `#` headings, `headings[1]`
+    /// `##` headings, and `headings[2]` `###` headings.
+    pub headings: [usize; 3],
+    /// The number of whitespace separated words in text lines. Headings, links, list items,
+    /// quotes and preformatted blocks aren't counted.
+    pub words: usize,
+    /// The number of preformatted blocks.
+    pub preformatted_blocks: usize,
+}
+
+impl Gemtext {
+    /// Counts the document's links, headings, words and preformatted blocks in one pass.
+    ///
+    /// `base` is the URL the document was fetched from. A link is internal if it resolves
+    /// against `base` to the same scheme, host and port, and external otherwise. Without a
+    /// `base`, relative links are internal and absolute ones are external.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Gemtext;
+    ///
+    /// let doc = Gemtext::new(
+    ///     "# Capsule\n## Posts\nWelcome to my capsule\n=> /posts/ Posts\n\
+    ///      => gemini://EXAMPLE.org:1965/about About\n=> gemini://example.com/ Friend\n```\nart\n```",
+    /// )
+    /// .unwrap();
+    /// let base = url::Url::parse("gemini://example.org/").unwrap();
+    ///
+    /// let stats = doc.stats(Some(&base));
+    /// assert_eq!(stats.links, 3);
+    /// assert_eq!(stats.internal_links, 2);
+    /// assert_eq!(stats.external_links, 1);
+    /// assert_eq!(stats.headings, [1, 1, 0]);
+    /// assert_eq!(stats.words, 4);
+    /// assert_eq!(stats.preformatted_blocks, 1);
+    ///
+    /// assert_eq!(doc.stats(None).internal_links, 1);
+    /// ```
+    #[must_use]
+    pub fn stats(&self, base: Option<&url::Url>) -> GemtextStats {
+        let base = base.map(|base| urls::normalize(base.clone()));
+        let mut stats = GemtextStats::default();
+
+        for element in &self.elements {
+            match element {
+                Element::Text(text) => stats.words += text.split_whitespace().count(),
+                Element::Link(url, _text) => {
+                    stats.links += 1;
+                    let internal = match &base {
+                        Some(base) => base.join(url).is_ok_and(|url| {
+                            let url = urls::normalize(url);
+                            url.scheme() == base.scheme()
+                                && url.host_str() == base.host_str()
+                                && url.port() == base.port()
+                        }),
+                        None => {
+                            url::Url::parse(url) == Err(url::ParseError::RelativeUrlWithoutBase)
+                        }
+                    };
+                    if internal {
+                        stats.internal_links += 1;
+                    } else {
+                        stats.external_links += 1;
+                    }
+                }
+                Element::Heading(_) => stats.headings[0] += 1,
+                Element::Subheading(_) => stats.headings[1] += 1,
+                Element::Subsubheading(_) => stats.headings[2] += 1,
+                Element::Preformatted(..) => stats.preformatted_blocks += 1,
+                _ => {}
+            }
+        }
+
+        stats
+    }
+}
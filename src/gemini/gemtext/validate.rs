@@ -0,0 +1,160 @@
+use super::Gemtext;
+
+/// Lines longer than this many characters are flagged by [`Gemtext::validate`]. The gemini
+/// specification doesn't limit the length of lines, this is only advice: clients wrap text lines,
+/// but preformatted lines are shown as they are, and a line this long is more often a mistake than
+/// not.
+pub const MAX_LINE_LENGTH: usize = 1024;
+
+/// Represents the kinds of problem [`Gemtext::validate`] looks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A link line with nothing after the `=>`.
+    EmptyLink,
+    /// A heading line with nothing after its `#`s.
+    EmptyHeading,
+    /// A preformatted block whose closing ```` ``` ```` is missing, so it runs to the end of the
+    /// document.
+    UnclosedPreformatted,
+    /// A line longer than [`MAX_LINE_LENGTH`] characters, holding its length in characters.
+    LongLine(usize),
+}
+
+/// Represents a problem found in a document, see [`Gemtext::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationWarning {
+    /// The line the problem is on, starting at 1.
+    pub line: usize,
+    /// What kind of problem it is.
+    pub kind: WarningKind,
+    /// A human readable description of the problem.
+    pub message: String,
+}
+
+impl ValidationWarning {
+    fn new(line: usize, kind: WarningKind) -> ValidationWarning {
+        let message = match kind {
+            WarningKind::EmptyLink => {
+                String::from("Link has no target, there must be a URL after =>")
+            }
+            WarningKind::EmptyHeading => String::from("Heading has no text"),
+            WarningKind::UnclosedPreformatted => {
+                String::from("Preformatted block is never closed with ```")
+            }
+            WarningKind::LongLine(length) => format!(
+                "Line is {} characters long, more than the {} this crate advises",
+                length, MAX_LINE_LENGTH
+            ),
+        };
+
+        ValidationWarning {
+            line,
+            kind,
+            message,
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "Line #{}: {}", self.line, self.message)
+    }
+}
+
+impl Gemtext {
+    /// Checks the document for problems a capsule author would want fixed before publishing it:
+    /// links without a target, headings without text, and overly long lines. Line numbers are
+    /// those of the document as it's written by its [`Display`](std::fmt::Display)
+    /// implementation.
+    ///
+    /// Unlike [`Gemtext::parse_lenient`], nothing is recovered from, problems are only reported.
+    /// Some problems, like an unclosed preformatted block, can't be seen once a document is
+    /// parsed, use [`Gemtext::validate_source`] to check a document's source directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Gemtext, WarningKind};
+    ///
+    /// let (doc, _warnings) = Gemtext::parse_lenient("# Links\n=>\n##\n");
+    /// let warnings = doc.validate();
+    /// assert_eq!(warnings[0].line, 2);
+    /// assert_eq!(warnings[0].kind, WarningKind::EmptyLink);
+    /// assert_eq!(warnings[1].kind, WarningKind::EmptyHeading);
+    /// ```
+    #[must_use]
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        Self::validate_source(&self.to_string())
+    }
+
+    /// Checks the source of a document for the problems [`Gemtext::validate`] looks for, as well
+    /// as preformatted blocks that are never closed. The warnings are in line order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::{Gemtext, WarningKind};
+    ///
+    /// let warnings = Gemtext::validate_source("# Art\n```\n=^.^=\n");
+    /// assert_eq!(warnings.len(), 1);
+    /// assert_eq!(warnings[0].line, 2);
+    /// assert_eq!(warnings[0].kind, WarningKind::UnclosedPreformatted);
+    /// ```
+    #[must_use]
+    pub fn validate_source(input: &str) -> Vec<ValidationWarning> {
+        let input = input.strip_prefix('\u{feff}').unwrap_or(input);
+        let mut warnings = Vec::new();
+        // The line the open preformatted block started on, if there is one.
+        let mut preformatted = None;
+
+        for (index, line) in input.lines().enumerate() {
+            let line_number = index + 1;
+
+            let length = line.chars().count();
+            if length > MAX_LINE_LENGTH {
+                warnings.push(ValidationWarning::new(
+                    line_number,
+                    WarningKind::LongLine(length),
+                ));
+            }
+
+            if line.starts_with("```") {
+                preformatted = match preformatted {
+                    Some(_) => None,
+                    None => Some(line_number),
+                };
+                continue;
+            }
+            // Anything goes inside a preformatted block.
+            if preformatted.is_some() {
+                continue;
+            }
+
+            let heading = line
+                .strip_prefix("###")
+                .or_else(|| line.strip_prefix("##"))
+                .or_else(|| line.strip_prefix('#'));
+            if line
+                .strip_prefix("=>")
+                .is_some_and(|link| link.trim().is_empty())
+            {
+                warnings.push(ValidationWarning::new(line_number, WarningKind::EmptyLink));
+            } else if heading.is_some_and(|text| text.trim().is_empty()) {
+                warnings.push(ValidationWarning::new(
+                    line_number,
+                    WarningKind::EmptyHeading,
+                ));
+            }
+        }
+
+        if let Some(line_number) = preformatted {
+            warnings.push(ValidationWarning::new(
+                line_number,
+                WarningKind::UnclosedPreformatted,
+            ));
+            warnings.sort_by_key(|warning| warning.line);
+        }
+
+        warnings
+    }
+}
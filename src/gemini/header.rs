@@ -3,7 +3,8 @@ use std::str::FromStr;
 use super::Error;
 
 /// Represents the header sent back from a server's response.
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Header {
     /// The status code of the response.
     pub status: StatusCode,
@@ -13,7 +14,22 @@ pub struct Header {
 }
 
 /// Represents a status code from a server's response header.
-#[derive(Clone, Copy)]
+///
+/// Status codes can be compared and hashed, so they can key a map.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::header::StatusCode;
+/// use std::collections::HashMap;
+///
+/// let mut counts = HashMap::new();
+/// for code in ["20", "51", "20"] {
+///     *counts.entry(code.parse::<StatusCode>().unwrap()).or_insert(0) += 1;
+/// }
+/// assert_eq!(counts[&StatusCode::Success], 2);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum StatusCode {
     Input(InputCode),
     Success,
@@ -24,21 +40,21 @@ pub enum StatusCode {
 }
 
 /// Represents the subtypes of input a server can ask for.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum InputCode {
     Input,
     Sensitive,
 }
 
 /// Represents the subtypes of redirects a server can ask for.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum RedirectCode {
     Temporary,
     Permanent,
 }
 
 /// Represents the subtypes of temporary failure a server can have.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FailTemporaryCode {
     Temporary,
     ServerUnavailable,
@@ -47,7 +63,7 @@ pub enum FailTemporaryCode {
     SlowDown,
 }
 /// Represents the subtypes of permanent failure a server can have.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum FailPermanentCode {
     Permanent,
     NotFound,
@@ -57,7 +73,7 @@ pub enum FailPermanentCode {
 }
 
 /// Represents the subtypes of certificate failure a server can have.
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum CertFailCode {
     CertRequired,
     CertNotAuthorized,
@@ -68,16 +84,67 @@ impl TryFrom<String> for Header {
     type Error = Error;
 
     fn try_from(header: String) -> Result<Self, Error> {
-        // The proper format of a header is `<STATUS><SPACE><META><CR><LF>`.
+        Header::from_str(&header)
+    }
+}
+
+impl FromStr for Header {
+    type Err = Error;
+
+    /// Parses a header in the form `<STATUS><SPACE><META><CR><LF>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "20 text/gemini\r\n".parse().unwrap();
+    /// assert_eq!(header.meta, "text/gemini");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::HeaderFormat`] if the header is malformed.
+    fn from_str(header: &str) -> Result<Self, Self::Err> {
+        // The status meta info must end in "\r\n". Only the CRLF is removed, trim isn't what we
+        // want because trailing white space can be part of <META>.
+        match header.strip_suffix("\r\n") {
+            Some(line) => Header::parse_line(line),
+            None => Err(Error::HeaderFormat(String::from(
+                "Meta information for the header doesn't end in <CR><LF>",
+            ))),
+        }
+    }
+}
+
+impl Header {
+    /// Parses a header line whose `<CR><LF>` has already been removed, in the form
+    /// `<STATUS><SPACE><META>`, such as one read from a log or a test fixture. Parsing a header
+    /// as it was sent, with [`str::parse`] or [`TryFrom`], checks for the `<CR><LF>` and then does
+    /// the same.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header = Header::parse_line("20 text/gemini").unwrap();
+    /// assert_eq!(header, "20 text/gemini\r\n".parse().unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error::HeaderFormat`] if the header is malformed.
+    pub fn parse_line(header: &str) -> Result<Header, Error> {
+        // The proper format of a header line is `<STATUS><SPACE><META>`.
         // We must check everything is properly formatted before we interpret any part of it.
 
         // The checking involves splitting the string <STATUS> is always 2 integers which is 2
-        // bytes, <SPACE> is defined as 0x20, one byte, <META> must be at least 1 byte, and
-        // then <CR> is 1 byte, and <LF> is 1 byte.
+        // bytes, <SPACE> is defined as 0x20, one byte, and <META> is the rest.
 
         let space_index = 2;
         // Check if space is where it should be and split on it
-        let (status, meta) = if header.chars().nth(space_index).unwrap() == ' ' {
+        let (status, meta) = if header.as_bytes().get(space_index) == Some(&b' ') {
             // we don't want to split at the header index because then it will include the space in the meta info
             (&header[0..2], &header[3..])
         } else {
@@ -94,15 +161,6 @@ impl TryFrom<String> for Header {
                 header
             )));
         }
-        // The status meta info must end in "\r\n".
-        if !meta.ends_with("\r\n") {
-            return Err(Error::HeaderFormat(String::from(
-                "Meta information for the header doesn't end in <CR><LF>",
-            )));
-        }
-
-        // Remove the CRLF, trim isn't what we want because trailing white space can be part of <META>.
-        let meta = &meta[0..meta.len() - 2];
         // Header <META> cannot be longer than 1024, if its then the entire header is invalid.
         if meta.len() > 1024 {
             return Err(Error::HeaderFormat(format!(
@@ -119,9 +177,277 @@ impl TryFrom<String> for Header {
             meta: meta.to_string(),
         })
     }
+
+    /// Returns the MIME type of a successful response's body, lowercased and without any
+    /// parameters, e.g. `text/gemini` for `Text/Gemini; charset=utf-8`. A success with an empty
+    /// meta is `text/gemini`, as the spec says. Returns `None` if the status isn't a success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "20 Image/PNG\r\n".parse().unwrap();
+    /// assert_eq!(header.mime_type().as_deref(), Some("image/png"));
+    /// ```
+    #[must_use]
+    pub fn mime_type(&self) -> Option<String> {
+        if !matches!(self.status, StatusCode::Success) {
+            return None;
+        }
+
+        let essence = self.meta.split(';').next().unwrap_or_default().trim();
+        if essence.is_empty() {
+            Some(String::from("text/gemini"))
+        } else {
+            Some(essence.to_ascii_lowercase())
+        }
+    }
+
+    /// Returns whether the response is successful and its body is a gemtext document, whatever
+    /// the MIME type's casing or parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "20 Text/Gemini ; lang=en\r\n".parse().unwrap();
+    /// assert!(header.is_gemtext());
+    /// let header: Header = "20 text/gemini-extended\r\n".parse().unwrap();
+    /// assert!(!header.is_gemtext());
+    /// ```
+    #[must_use]
+    pub fn is_gemtext(&self) -> bool {
+        self.mime_type().as_deref() == Some("text/gemini")
+    }
+
+    /// Returns the value of the MIME parameter `name` from the meta, e.g. `charset` in
+    /// `text/gemini; charset=utf-8`. Parameter names are matched case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "20 text/gemini; charset=iso-8859-1\r\n".parse().unwrap();
+    /// assert_eq!(header.parameter("charset"), Some("iso-8859-1"));
+    /// assert_eq!(header.parameter("lang"), None);
+    /// ```
+    #[must_use]
+    pub fn parameter(&self, name: &str) -> Option<&str> {
+        self.meta.split(';').skip(1).find_map(|parameter| {
+            let (key, value) = parameter.split_once('=')?;
+            if key.trim().eq_ignore_ascii_case(name) {
+                Some(value.trim().trim_matches('"'))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Returns the `charset` parameter from the meta if the response declared one.
+    #[must_use]
+    pub fn charset(&self) -> Option<&str> {
+        self.parameter("charset")
+    }
+
+    /// Returns the languages a successful response declared its body is in with the `lang`
+    /// parameter, e.g. `["en", "fr"]` for `text/gemini; lang=en,fr`. Returns `None` if it didn't
+    /// declare any, or the status isn't a success.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "20 text/gemini; lang=en, fr\r\n".parse().unwrap();
+    /// assert_eq!(header.lang(), Some(vec![String::from("en"), String::from("fr")]));
+    /// ```
+    #[must_use]
+    pub fn lang(&self) -> Option<Vec<String>> {
+        if !matches!(self.status, StatusCode::Success) {
+            return None;
+        }
+
+        let tags: Vec<String> = self
+            .parameter("lang")?
+            .split(',')
+            .map(str::trim)
+            .filter(|tag| !tag.is_empty())
+            .map(String::from)
+            .collect();
+        if tags.is_empty() {
+            None
+        } else {
+            Some(tags)
+        }
+    }
+
+    /// Returns the prompt to show the user if the server is asking for input, which is the meta
+    /// of a `10` or `11` response. Returns `None` for any other status.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "11 Password\r\n".parse().unwrap();
+    /// assert_eq!(header.input_prompt(), Some("Password"));
+    /// assert!(header.is_sensitive_input());
+    /// ```
+    #[must_use]
+    pub fn input_prompt(&self) -> Option<&str> {
+        match self.status {
+            StatusCode::Input(_) => Some(&self.meta),
+            _ => None,
+        }
+    }
+
+    /// Returns whether the server is asking for sensitive input, such as a password, which
+    /// shouldn't be echoed as the user types it.
+    #[must_use]
+    pub fn is_sensitive_input(&self) -> bool {
+        matches!(self.status, StatusCode::Input(InputCode::Sensitive))
+    }
+
+    /// Returns the URL a redirect points to, which is the meta resolved against `base`, the URL
+    /// that was requested. Returns `Ok(None)` if the status isn't a redirect.
+    ///
+    /// Relative targets are resolved, and characters that aren't allowed in a URL are
+    /// percent-encoded, so the result can be requested as is.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Header;
+    ///
+    /// let header: Header = "31 ../new%20home/\r\n".parse().unwrap();
+    /// let url = header.redirect_url("gemini://example.org/old/page").unwrap().unwrap();
+    /// assert_eq!(url.as_str(), "gemini://example.org/new%20home/");
+    ///
+    /// let header: Header = "20 text/gemini\r\n".parse().unwrap();
+    /// assert!(header.redirect_url("gemini://example.org/").unwrap().is_none());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::UrlParse`] if `base` or the redirect target isn't a valid URL, or
+    /// an [`Error::HeaderFormat`] if the redirect has no target.
+    pub fn redirect_url(&self, base: &str) -> Result<Option<url::Url>, Error> {
+        if !matches!(self.status, StatusCode::Redirect(_)) {
+            return Ok(None);
+        }
+        let target = self.meta.trim();
+        if target.is_empty() {
+            return Err(Error::HeaderFormat(String::from(
+                "A redirect must have a URL to redirect to as its meta",
+            )));
+        }
+
+        let base = url::Url::parse(base)?;
+        Ok(Some(base.join(target)?))
+    }
 }
 
 impl StatusCode {
+    /// Every status code, in numeric order.
+    pub const ALL: [StatusCode; 18] = [
+        StatusCode::Input(InputCode::Input),
+        StatusCode::Input(InputCode::Sensitive),
+        StatusCode::Success,
+        StatusCode::Redirect(RedirectCode::Temporary),
+        StatusCode::Redirect(RedirectCode::Permanent),
+        StatusCode::FailTemporary(FailTemporaryCode::Temporary),
+        StatusCode::FailTemporary(FailTemporaryCode::ServerUnavailable),
+        StatusCode::FailTemporary(FailTemporaryCode::CGIError),
+        StatusCode::FailTemporary(FailTemporaryCode::ProxyError),
+        StatusCode::FailTemporary(FailTemporaryCode::SlowDown),
+        StatusCode::FailPermanent(FailPermanentCode::Permanent),
+        StatusCode::FailPermanent(FailPermanentCode::NotFound),
+        StatusCode::FailPermanent(FailPermanentCode::Gone),
+        StatusCode::FailPermanent(FailPermanentCode::ProxyRefused),
+        StatusCode::FailPermanent(FailPermanentCode::BadRequest),
+        StatusCode::CertFail(CertFailCode::CertRequired),
+        StatusCode::CertFail(CertFailCode::CertNotAuthorized),
+        StatusCode::CertFail(CertFailCode::CertNotValid),
+    ];
+
+    /// Returns an iterator over every status code, in numeric order, see [`StatusCode::ALL`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::header::StatusCode;
+    ///
+    /// for status in StatusCode::all() {
+    ///     println!("{} {}", status, status.description());
+    /// }
+    /// assert_eq!(StatusCode::all().count(), 18);
+    /// ```
+    pub fn all() -> impl Iterator<Item = StatusCode> {
+        StatusCode::ALL.into_iter()
+    }
+
+    /// Returns the name the gemini spec gives the status code, e.g. `"Not Found"` for `51`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::header::StatusCode;
+    ///
+    /// assert_eq!("44".parse::<StatusCode>().unwrap().description(), "Slow Down");
+    /// ```
+    #[must_use]
+    pub fn description(&self) -> &'static str {
+        match self {
+            StatusCode::Input(InputCode::Input) => "Input",
+            StatusCode::Input(InputCode::Sensitive) => "Sensitive Input",
+            StatusCode::Success => "Success",
+            StatusCode::Redirect(RedirectCode::Temporary) => "Temporary Redirect",
+            StatusCode::Redirect(RedirectCode::Permanent) => "Permanent Redirect",
+            StatusCode::FailTemporary(FailTemporaryCode::Temporary) => "Temporary Failure",
+            StatusCode::FailTemporary(FailTemporaryCode::ServerUnavailable) => "Server Unavailable",
+            StatusCode::FailTemporary(FailTemporaryCode::CGIError) => "CGI Error",
+            StatusCode::FailTemporary(FailTemporaryCode::ProxyError) => "Proxy Error",
+            StatusCode::FailTemporary(FailTemporaryCode::SlowDown) => "Slow Down",
+            StatusCode::FailPermanent(FailPermanentCode::Permanent) => "Permanent Failure",
+            StatusCode::FailPermanent(FailPermanentCode::NotFound) => "Not Found",
+            StatusCode::FailPermanent(FailPermanentCode::Gone) => "Gone",
+            StatusCode::FailPermanent(FailPermanentCode::ProxyRefused) => "Proxy Request Refused",
+            StatusCode::FailPermanent(FailPermanentCode::BadRequest) => "Bad Request",
+            StatusCode::CertFail(CertFailCode::CertRequired) => "Client Certificate Required",
+            StatusCode::CertFail(CertFailCode::CertNotAuthorized) => "Certificate Not Authorised",
+            StatusCode::CertFail(CertFailCode::CertNotValid) => "Certificate Not Valid",
+        }
+    }
+
+    /// Returns the numeric value of the status code, e.g. `51` for
+    /// [`FailPermanentCode::NotFound`].
+    #[must_use]
+    pub fn code(&self) -> u8 {
+        // Every status code string is exactly two digits.
+        let digits = self.to_str().as_bytes();
+        (digits[0] - b'0') * 10 + (digits[1] - b'0')
+    }
+
+    /// Returns whether a request that got this status might succeed if it's made again later,
+    /// which is the case for every temporary failure, `40` to `44`. A `44` response's meta is
+    /// the number of seconds to wait before trying again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::header::StatusCode;
+    ///
+    /// assert!("44".parse::<StatusCode>().unwrap().is_retriable());
+    /// assert!(!"51".parse::<StatusCode>().unwrap().is_retriable());
+    /// ```
+    #[must_use]
+    pub fn is_retriable(&self) -> bool {
+        matches!(self, StatusCode::FailTemporary(_))
+    }
+
     pub fn to_str(&self) -> &'static str {
         match self {
             StatusCode::Input(InputCode::Input) => "10",
@@ -225,3 +551,19 @@ impl std::fmt::Display for Header {
         write!(f, "{}: {}", self.status, self.meta)
     }
 }
+
+/// Status codes serialize as their numeric value, e.g. `51`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for StatusCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for StatusCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = u8::deserialize(deserializer)?;
+        StatusCode::from_str(&code.to_string()).map_err(serde::de::Error::custom)
+    }
+}
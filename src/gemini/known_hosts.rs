@@ -0,0 +1,235 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+
+use super::Error;
+
+/// Represents a certificate that was trusted the first time a host was seen.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct KnownHost {
+    /// The SHA-256 fingerprint of the certificate, see [`fingerprint`].
+    pub fingerprint: String,
+    /// When the certificate was first seen, in seconds since the Unix epoch.
+    pub first_seen: u64,
+}
+
+/// Represents the certificates trusted for each host, following the trust on first use model
+/// most gemini clients use in place of certificate authorities.
+///
+/// The first certificate seen for a host is trusted and remembered, and any later connection
+/// presenting a different certificate is refused until the user decides what to do about it.
+///
+/// Known hosts are stored in a plain text file, one host per line in the form
+/// `<host>:<port> <fingerprint> <first seen>`, where the first seen time is in seconds since the
+/// Unix epoch. Blank lines and lines starting with `#` are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "rustls")]
+/// # fn main() {
+/// use leda::gemini::{Client, known_hosts::KnownHosts, tls::RustlsConnector};
+/// use std::sync::{Arc, Mutex};
+///
+/// let known_hosts = Arc::new(Mutex::new(KnownHosts::load("known_hosts").unwrap()));
+/// let mut client = Client::new().unwrap();
+/// client.set_connector(Arc::new(RustlsConnector::with_known_hosts(known_hosts.clone())));
+///
+/// let response = client.request(String::from("gemini://gemini.circumlunar.space/"));
+/// known_hosts.lock().unwrap().save("known_hosts").unwrap();
+/// # }
+/// # #[cfg(not(feature = "rustls"))]
+/// # fn main() {}
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct KnownHosts {
+    hosts: BTreeMap<String, KnownHost>,
+}
+
+/// Returns the fingerprint of a DER encoded certificate, its SHA-256 hash as lowercase hex
+/// prefixed by `sha256:`.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::known_hosts::fingerprint;
+///
+/// assert!(fingerprint(b"certificate").starts_with("sha256:"));
+/// ```
+#[must_use]
+pub fn fingerprint(certificate: &[u8]) -> String {
+    Sha256::digest(certificate)
+        .iter()
+        .fold(String::from("sha256:"), |mut output, byte| {
+            let _ = write!(output, "{:02x}", byte);
+            output
+        })
+}
+
+impl KnownHosts {
+    /// Creates an empty set of known hosts.
+    #[must_use]
+    pub fn new() -> KnownHosts {
+        KnownHosts::default()
+    }
+
+    /// Parses known hosts from the contents of a known hosts file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::known_hosts::KnownHosts;
+    ///
+    /// let known_hosts = KnownHosts::parse("# my hosts\n\nexample.org:1965 sha256:ab 1650000000\n")
+    ///     .unwrap();
+    /// assert_eq!(known_hosts.get("example.org:1965").unwrap().first_seen, 1650000000);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::KnownHostsFormat`] if a line is missing fields or has an invalid
+    /// first seen time.
+    pub fn parse(input: &str) -> Result<KnownHosts, Error> {
+        let mut hosts = BTreeMap::new();
+
+        for (index, line) in input.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.split_whitespace();
+            let (host, fingerprint, first_seen) = match (fields.next(), fields.next(), fields.next())
+            {
+                (Some(host), Some(fingerprint), Some(first_seen)) => {
+                    (host, fingerprint, first_seen)
+                }
+                _ => {
+                    return Err(Error::KnownHostsFormat(format!(
+                        "Known hosts must have a host, fingerprint, and first seen time \
+                        separated by spaces. Line #{}: {}",
+                        index + 1,
+                        line
+                    )))
+                }
+            };
+            let first_seen = first_seen.parse().map_err(|_| {
+                Error::KnownHostsFormat(format!(
+                    "Invalid first seen time \"{}\". Line #{}: {}",
+                    first_seen,
+                    index + 1,
+                    line
+                ))
+            })?;
+
+            hosts.insert(
+                host.to_string(),
+                KnownHost {
+                    fingerprint: fingerprint.to_string(),
+                    first_seen,
+                },
+            );
+        }
+
+        Ok(KnownHosts { hosts })
+    }
+
+    /// Loads known hosts from the file at `path`. A file that doesn't exist yet is treated as
+    /// empty.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::KnownHostsIO`] if the file couldn't be read, or an
+    /// [`Error::KnownHostsFormat`] if it's malformed.
+    pub fn load(path: impl AsRef<Path>) -> Result<KnownHosts, Error> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::parse(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(KnownHosts::new()),
+            Err(e) => Err(Error::KnownHostsIO(e)),
+        }
+    }
+
+    /// Saves the known hosts to the file at `path`, replacing it if it exists.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::KnownHostsIO`] if the file couldn't be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        std::fs::write(path, self.to_string()).map_err(Error::KnownHostsIO)
+    }
+
+    /// Returns the certificate trusted for `host`, which is in the form `<host>:<port>`.
+    #[must_use]
+    pub fn get(&self, host: &str) -> Option<&KnownHost> {
+        self.hosts.get(host)
+    }
+
+    /// Forgets the certificate trusted for `host`, so whichever certificate it presents next is
+    /// trusted. This is how a changed certificate is accepted.
+    pub fn remove(&mut self, host: &str) -> Option<KnownHost> {
+        self.hosts.remove(host)
+    }
+
+    /// Checks the DER encoded `certificate` presented by `host` against the one trusted for it,
+    /// trusting it if the host hasn't been seen before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::{Error, known_hosts::KnownHosts};
+    ///
+    /// let mut known_hosts = KnownHosts::new();
+    /// assert!(known_hosts.check("example.org:1965", b"first").is_ok());
+    /// assert!(known_hosts.check("example.org:1965", b"first").is_ok());
+    /// assert!(matches!(
+    ///     known_hosts.check("example.org:1965", b"second"),
+    ///     Err(Error::CertificateChanged(..))
+    /// ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::CertificateChanged`] holding the host, the trusted fingerprint,
+    /// and the presented fingerprint if they differ.
+    pub fn check(&mut self, host: &str, certificate: &[u8]) -> Result<(), Error> {
+        let presented = fingerprint(certificate);
+
+        match self.hosts.get(host) {
+            Some(known) if known.fingerprint == presented => Ok(()),
+            Some(known) => Err(Error::CertificateChanged(
+                host.to_string(),
+                known.fingerprint.clone(),
+                presented,
+            )),
+            None => {
+                let first_seen = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_secs())
+                    .unwrap_or(0);
+                self.hosts.insert(
+                    host.to_string(),
+                    KnownHost {
+                        fingerprint: presented,
+                        first_seen,
+                    },
+                );
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for KnownHosts {
+    /// Formats the known hosts as the contents of a known hosts file.
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "# <host>:<port> <fingerprint> <first seen>")?;
+        for (host, known) in &self.hosts {
+            writeln!(f, "{} {} {}", host, known.fingerprint, known.first_seen)?;
+        }
+
+        Ok(())
+    }
+}
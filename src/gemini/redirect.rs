@@ -0,0 +1,110 @@
+use std::fmt;
+use std::sync::Arc;
+
+use super::header::RedirectCode;
+use super::urls;
+
+/// Represents a redirect a server asked for, which a [`RedirectPolicy`] decides whether to
+/// follow.
+#[derive(Debug, Clone, Copy)]
+pub struct Redirect<'a> {
+    /// The URL that was requested.
+    pub from: &'a url::Url,
+    /// Where the server redirected to, resolved against `from`.
+    pub to: &'a url::Url,
+    /// Whether the redirect is temporary or permanent.
+    pub code: RedirectCode,
+}
+
+impl Redirect<'_> {
+    /// Returns whether the redirect leads to a different host or port, which means following
+    /// it connects to a different server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::header::RedirectCode;
+    /// use leda::gemini::Redirect;
+    ///
+    /// let from = url::Url::parse("gemini://example.org/old").unwrap();
+    /// let to = url::Url::parse("gemini://EXAMPLE.org:1965/new").unwrap();
+    /// let redirect = Redirect { from: &from, to: &to, code: RedirectCode::Permanent };
+    /// assert!(!redirect.is_cross_host());
+    ///
+    /// let to = url::Url::parse("gemini://example.com/new").unwrap();
+    /// let redirect = Redirect { from: &from, to: &to, code: RedirectCode::Permanent };
+    /// assert!(redirect.is_cross_host());
+    /// ```
+    #[must_use]
+    pub fn is_cross_host(&self) -> bool {
+        let from = urls::normalize(self.from.clone());
+        let to = urls::normalize(self.to.clone());
+
+        from.host_str() != to.host_str() || from.port() != to.port()
+    }
+
+    /// Returns whether the redirect leads to a URL with a different scheme, such as from
+    /// `gemini` to `http`.
+    #[must_use]
+    pub fn is_cross_scheme(&self) -> bool {
+        self.from.scheme() != self.to.scheme()
+    }
+}
+
+/// Represents which redirects a [`super::Client`] follows by itself, see
+/// [`super::Client::set_redirect_policy`].
+///
+/// Redirects to another scheme are never followed by the client, whatever the policy, so a
+/// [`RedirectPolicy::Confirm`] callback is only asked about redirects to other gemini hosts.
+#[derive(Clone, Default)]
+pub enum RedirectPolicy {
+    /// Don't follow redirects, return them as responses. This is the default.
+    #[default]
+    Never,
+    /// Follow redirects to the same host and port, return redirects to other hosts as
+    /// responses.
+    SameHost,
+    /// Follow redirects to any host.
+    AnyHost,
+    /// Follow redirects to the same host, and ask the callback whether to follow redirects to
+    /// other hosts. Those the callback returns `false` for are returned as responses.
+    Confirm(Arc<dyn Fn(&Redirect) -> bool + Send + Sync>),
+}
+
+impl RedirectPolicy {
+    /// Returns whether `redirect` should be followed under this policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::header::RedirectCode;
+    /// use leda::gemini::{Redirect, RedirectPolicy};
+    ///
+    /// let from = url::Url::parse("gemini://example.org/").unwrap();
+    /// let to = url::Url::parse("https://example.org/").unwrap();
+    /// let redirect = Redirect { from: &from, to: &to, code: RedirectCode::Temporary };
+    /// assert!(!RedirectPolicy::AnyHost.allows(&redirect));
+    /// ```
+    #[must_use]
+    pub fn allows(&self, redirect: &Redirect) -> bool {
+        let same_server = !redirect.is_cross_scheme() && !redirect.is_cross_host();
+
+        match self {
+            RedirectPolicy::Never => false,
+            RedirectPolicy::SameHost => same_server,
+            RedirectPolicy::AnyHost => !redirect.is_cross_scheme(),
+            RedirectPolicy::Confirm(confirm) => same_server || confirm(redirect),
+        }
+    }
+}
+
+impl fmt::Debug for RedirectPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RedirectPolicy::Never => write!(f, "Never"),
+            RedirectPolicy::SameHost => write!(f, "SameHost"),
+            RedirectPolicy::AnyHost => write!(f, "AnyHost"),
+            RedirectPolicy::Confirm(_) => write!(f, "Confirm(..)"),
+        }
+    }
+}
@@ -1,19 +1,206 @@
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use encoding_rs::{Encoding, UTF_8};
+
 use super::header;
+use super::{Error, Gemtext};
 
 /// Represents a response generated from a gemini server.
-#[derive(Clone)]
+///
+/// Responses compare equal if they got back the same thing from the same URL, `timing` and
+/// `connection` describe how a particular request went and are left out of the comparison, so a
+/// response from a client can be checked against an expected one.
+#[derive(Clone, Debug, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Response {
     /// The header the server responded with, includes the response status code as well as the meta
     /// information provided.
     pub header: header::Header,
     /// The response body content from the server. `body` will only be `Some` if the header's
     /// [`header::Header::status`] is [`header::StatusCode::Success`], otherwise it'll be `None`.
+    /// Any bytes a server sends after a non-success header are discarded.
     pub body: Option<Vec<u8>>,
+    /// Whether `body` may be incomplete. This is the case when the connection was closed without
+    /// the server signalling the end of the response with a TLS `close_notify`, or when the body
+    /// was cut off at the client's [`super::Client::set_max_body_size`].
+    pub truncated: bool,
+    /// How long each phase of the request took. This is only `Some` for responses returned by a
+    /// [`super::Client`]'s blocking requests.
+    pub timing: Option<Timing>,
+    /// Where the request was sent. Like `timing`, this is only `Some` for responses returned by
+    /// a [`super::Client`]'s blocking requests.
+    pub connection: Option<ConnectionInfo>,
+    /// The URL that was requested, without the `<CR><LF>` ending the request, which is the URL
+    /// relative links in the body are resolved against. This is only `Some` for responses
+    /// returned by a [`super::Client`].
+    pub url: Option<String>,
+    /// The redirects followed on the way to this response, oldest first, as the URL that was
+    /// requested and the redirect status it responded with. The last URL redirected to is `url`.
+    /// This is empty unless the client's [`super::RedirectPolicy`] followed redirects.
+    pub history: Vec<(url::Url, header::StatusCode)>,
+}
+
+/// Represents where a request was actually sent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ConnectionInfo {
+    /// The `<host>:<port>` that was connected to.
+    pub host: String,
+    /// The server name sent in the TLS handshake, which is the URL's host unless the client was
+    /// given another with [`super::Client::set_server_name`].
+    pub server_name: String,
+    /// The address the connection was made to, out of all the ones the host resolved to. When
+    /// connecting through a proxy this is the proxy's address. This is `None` for connections
+    /// opened by a custom [`super::transport::Transport`].
+    pub address: Option<SocketAddr>,
+    /// Whether the TLS session was resumed from an earlier connection to the host, skipping a
+    /// full handshake. This is `None` when the connection can't tell, which is the case for
+    /// `native-tls` connections, a [`super::tls::RustlsConnector`] made with its own rustls
+    /// configuration, and custom transports.
+    pub session_resumed: Option<bool>,
+}
+
+/// Represents how long the phases of a request took.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timing {
+    /// Time spent resolving the host and opening the connection, including the TLS handshake for
+    /// connectors that make it straight away, see `transfer`.
+    pub connect: Duration,
+    /// Time spent sending the request and reading the response. Where the TLS handshake is
+    /// counted depends on the connector: a [`super::tls::RustlsConnector`] makes it along with
+    /// sending the request, so it's counted here, unless it trusts on first use and has to see the
+    /// certificate before sending anything. That one, and `native-tls` connectors, make it while
+    /// connecting, so it's counted in `connect`.
+    pub transfer: Duration,
+    /// Time spent on the whole request, from opening the connection to parsing the response. Time
+    /// spent waiting on a [`super::Client::set_rate_limit`] isn't included.
+    pub total: Duration,
+}
+
+impl PartialEq for Response {
+    fn eq(&self, other: &Self) -> bool {
+        self.header == other.header
+            && self.body == other.body
+            && self.truncated == other.truncated
+            && self.url == other.url
+            && self.history == other.history
+    }
 }
 
 impl Response {
     #[must_use]
     pub fn new(header: header::Header, body: Option<Vec<u8>>) -> Response {
-        Response { header, body }
+        Response {
+            header,
+            body,
+            truncated: false,
+            timing: None,
+            connection: None,
+            url: None,
+            history: Vec::new(),
+        }
+    }
+
+    /// Takes the body as [`bytes::Bytes`], which can be cloned and sliced without copying it, to
+    /// share it between threads or tasks. The body isn't copied to convert it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Response;
+    ///
+    /// let response = Response::new("20 text/gemini\r\n".parse().unwrap(), Some(b"# Hi\n".to_vec()));
+    /// let body = response.into_bytes().unwrap();
+    /// let copies = [body.clone(), body.clone()];
+    /// assert_eq!(copies[1], "# Hi\n");
+    /// ```
+    #[cfg(feature = "bytes")]
+    #[must_use]
+    pub fn into_bytes(self) -> Option<bytes::Bytes> {
+        self.body.map(bytes::Bytes::from)
+    }
+
+    /// Decodes the body as text using the `charset` declared in the header's meta, falling back
+    /// to UTF-8 if none was declared or the charset isn't recognized. Sequences that are invalid
+    /// in the charset are replaced with `U+FFFD`. Returns `None` if there's no body.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Response;
+    ///
+    /// let header = "20 text/plain; charset=iso-8859-1\r\n".parse().unwrap();
+    /// let response = Response::new(header, Some(b"caf\xe9".to_vec()));
+    /// assert_eq!(response.text().unwrap(), "café");
+    /// ```
+    #[must_use]
+    pub fn text(&self) -> Option<String> {
+        let body = self.body.as_ref()?;
+
+        Some(self.encoding().decode(body).0.into_owned())
+    }
+
+    /// Parses the body as a [`Gemtext`] document, checking that the response was successful and
+    /// is gemtext, see [`header::Header::is_gemtext`], along the way. The body is decoded using
+    /// the `charset` declared in the header's meta, or UTF-8 if there isn't one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::Response;
+    ///
+    /// let header = "20 text/gemini\r\n".parse().unwrap();
+    /// let response = Response::new(header, Some(b"# Hello\n".to_vec()));
+    /// assert_eq!(response.into_gemtext().unwrap().title(), Some("Hello"));
+    ///
+    /// let response = Response::new("51 Not found\r\n".parse().unwrap(), None);
+    /// assert!(response.into_gemtext().is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::NotGemtext`] if the response wasn't successful, isn't gemtext, has
+    /// no body, or the body isn't valid in its charset, and an [`Error::GemtextFormat`] if the
+    /// document is malformed.
+    pub fn into_gemtext(self) -> Result<Gemtext, Error> {
+        if !matches!(self.header.status, header::StatusCode::Success) {
+            return Err(Error::NotGemtext(format!(
+                "the status was {} {}, {}",
+                self.header.status,
+                self.header.status.description(),
+                self.header.meta
+            )));
+        }
+        if !self.header.is_gemtext() {
+            return Err(Error::NotGemtext(format!(
+                "the MIME type was {}",
+                self.header.meta
+            )));
+        }
+        let body = self
+            .body
+            .as_ref()
+            .ok_or_else(|| Error::NotGemtext(String::from("there was no body")))?;
+
+        let (text, encoding, had_errors) = self.encoding().decode(body);
+        if had_errors {
+            return Err(Error::NotGemtext(format!(
+                "the body isn't valid {}",
+                encoding.name()
+            )));
+        }
+
+        Gemtext::new(&text)
+    }
+
+    /// Returns the encoding the `charset` in the header's meta names, or UTF-8 if none was
+    /// declared or the charset isn't recognized.
+    fn encoding(&self) -> &'static Encoding {
+        self.header
+            .charset()
+            .and_then(|charset| Encoding::for_label(charset.as_bytes()))
+            .unwrap_or(UTF_8)
     }
 }
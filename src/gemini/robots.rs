@@ -0,0 +1,123 @@
+use super::header::StatusCode;
+use super::{Client, Error};
+
+/// Represents the rules of a capsule's `robots.txt`, which tells automated clients which parts of
+/// the capsule they shouldn't request.
+///
+/// Gemini crawlers identify themselves with one of a few virtual user agents describing what
+/// they do rather than their name, `archiver`, `indexer`, `researcher`, or `webproxy`. Rules for
+/// the `*` user agent apply to every client.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::robots::Robots;
+///
+/// let robots = Robots::parse("User-agent: indexer\nDisallow: /private/\n");
+/// assert!(!robots.is_allowed("/private/diary.gmi", "indexer"));
+/// assert!(robots.is_allowed("/private/diary.gmi", "archiver"));
+/// assert!(robots.is_allowed("/index.gmi", "indexer"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Robots {
+    groups: Vec<Group>,
+}
+
+/// A set of user agents and the paths they're disallowed from.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+struct Group {
+    agents: Vec<String>,
+    disallow: Vec<String>,
+}
+
+impl Robots {
+    /// Parses the contents of a `robots.txt` file.
+    ///
+    /// Each group of consecutive `User-agent` lines is followed by the `Disallow` lines that
+    /// apply to them. Comments, blank lines, and any other fields are ignored.
+    #[must_use]
+    pub fn parse(input: &str) -> Robots {
+        let mut groups: Vec<Group> = Vec::new();
+        // Whether the last rule was a user agent, meaning the next one belongs in the same group.
+        let mut in_agents = false;
+
+        for line in input.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+
+            if field.trim().eq_ignore_ascii_case("user-agent") {
+                if !in_agents {
+                    groups.push(Group::default());
+                }
+                in_agents = true;
+                if let Some(group) = groups.last_mut() {
+                    group.agents.push(value.to_ascii_lowercase());
+                }
+            } else if field.trim().eq_ignore_ascii_case("disallow") {
+                in_agents = false;
+                // An empty disallow allows everything, and rules before any user agent belong to
+                // no one.
+                if let (Some(group), false) = (groups.last_mut(), value.is_empty()) {
+                    group.disallow.push(value.to_string());
+                }
+            }
+        }
+
+        Robots { groups }
+    }
+
+    /// Fetches and parses the `robots.txt` of the capsule `url` is on.
+    ///
+    /// A capsule that doesn't successfully serve a `robots.txt` allows everything.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::gemini::{Client, robots::Robots};
+    ///
+    /// let client = Client::new().unwrap();
+    /// let robots = Robots::fetch(&client, "gemini://gemini.circumlunar.space/docs/").unwrap();
+    /// if robots.is_allowed("/docs/", "indexer") {
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if `url` is malformed or the request failed.
+    pub fn fetch(client: &Client, url: &str) -> Result<Robots, Error> {
+        let mut robots_url = url::Url::parse(url)?;
+        robots_url.set_path("/robots.txt");
+        robots_url.set_query(None);
+        robots_url.set_fragment(None);
+
+        let response = client.request(robots_url.to_string())?;
+        match (response.header.status, response.text()) {
+            (StatusCode::Success, Some(text)) => Ok(Self::parse(&text)),
+            _ => Ok(Robots::default()),
+        }
+    }
+
+    /// Returns whether a client identifying as the virtual user agent `agent` may request
+    /// `path`.
+    ///
+    /// `path` is disallowed if it starts with any path disallowed for `agent` or for `*`, user
+    /// agents are compared case insensitively.
+    #[must_use]
+    pub fn is_allowed(&self, path: &str, agent: &str) -> bool {
+        !self
+            .groups
+            .iter()
+            .filter(|group| {
+                group
+                    .agents
+                    .iter()
+                    .any(|group_agent| group_agent == "*" || group_agent.eq_ignore_ascii_case(agent))
+            })
+            .flat_map(|group| &group.disallow)
+            .any(|disallowed| path.starts_with(disallowed.as_str()))
+    }
+}
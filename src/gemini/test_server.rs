@@ -0,0 +1,145 @@
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// A gemini server running on a background thread that answers every request with the same
+/// canned response, for testing clients without a real server.
+///
+/// The server listens on a random port on the loopback interface and serves a freshly generated
+/// self-signed certificate for `localhost`. It's available with the `test-util` feature, and
+/// stops when dropped.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::{Client, test_server::TestServer};
+///
+/// let server = TestServer::new("20 text/gemini\r\n# Hello!\n");
+/// let client = Client::new().unwrap();
+///
+/// let response = client.request(server.url("/hello")).unwrap();
+/// assert_eq!(response.body.unwrap(), b"# Hello!\n");
+/// assert_eq!(server.requests(), [server.url("/hello")]);
+/// ```
+pub struct TestServer {
+    address: SocketAddr,
+    requests: Arc<Mutex<Vec<String>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl TestServer {
+    /// Starts a server that responds to every request with `response`, which should include
+    /// the header.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the server couldn't be started.
+    pub fn new(response: impl Into<Vec<u8>>) -> TestServer {
+        let response = response.into();
+        let certificate = rcgen::generate_simple_self_signed(vec![String::from("localhost")])
+            .expect("Failed to generate a certificate");
+        let config = rustls::ServerConfig::builder()
+            .with_safe_defaults()
+            .with_no_client_auth()
+            .with_single_cert(
+                vec![rustls::Certificate(
+                    certificate
+                        .serialize_der()
+                        .expect("Failed to serialize the certificate"),
+                )],
+                rustls::PrivateKey(certificate.serialize_private_key_der()),
+            )
+            .expect("Failed to configure TLS");
+        let config = Arc::new(config);
+
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Failed to bind a port");
+        let address = listener.local_addr().expect("Failed to get the bound port");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread_requests = requests.clone();
+        let thread_stopped = stopped.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if thread_stopped.load(Ordering::SeqCst) {
+                    break;
+                }
+                // A client that misbehaves only fails its own request.
+                if let Ok(stream) = stream {
+                    let _ = serve(config.clone(), stream, &response, &thread_requests);
+                }
+            }
+        });
+
+        TestServer {
+            address,
+            requests,
+            stopped,
+        }
+    }
+
+    /// Returns the address the server is listening on.
+    #[must_use]
+    pub fn address(&self) -> SocketAddr {
+        self.address
+    }
+
+    /// Returns a URL for `path` on the server, e.g. `gemini://localhost:<port>/path`.
+    #[must_use]
+    pub fn url(&self, path: &str) -> String {
+        format!(
+            "gemini://localhost:{}/{}",
+            self.address.port(),
+            path.trim_start_matches('/')
+        )
+    }
+
+    /// Returns the request line of each request served so far, without the <CR><LF>.
+    #[must_use]
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Drop for TestServer {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+        // Wake the server thread up so it notices it's been stopped.
+        let _ = TcpStream::connect(self.address);
+    }
+}
+
+/// Reads one request from `stream`, records it, and responds with `response`.
+fn serve(
+    config: Arc<rustls::ServerConfig>,
+    mut stream: TcpStream,
+    response: &[u8],
+    requests: &Mutex<Vec<String>>,
+) -> std::io::Result<()> {
+    let mut conn = rustls::ServerConnection::new(config).map_err(std::io::Error::other)?;
+    let mut tls = rustls::Stream::new(&mut conn, &mut stream);
+
+    let mut request = Vec::new();
+    let mut chunk = [0; 1024];
+    while !request.ends_with(b"\r\n") && request.len() <= 1026 {
+        let read = tls.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        request.extend_from_slice(&chunk[..read]);
+    }
+    let request = String::from_utf8_lossy(&request);
+    requests
+        .lock()
+        .unwrap()
+        .push(request.trim_end_matches("\r\n").to_string());
+
+    tls.write_all(response)?;
+    tls.flush()?;
+    conn.send_close_notify();
+    conn.complete_io(&mut stream)?;
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+/// Characters that can't appear unescaped in a titan parameter value.
+const PARAMETER: &AsciiSet = &CONTROLS.add(b' ').add(b'%').add(b';').add(b'=').add(b'?').add(b'#');
+
+/// Represents an upload to a titan server.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::TitanRequest;
+///
+/// let request = TitanRequest::new(String::from("titan://example.org/notes.txt"),
+///                                 b"hello".to_vec())
+///     .token(String::from("hunter2"))
+///     .mime(String::from("text/plain"));
+/// assert_eq!(request.request_line(),
+///            "titan://example.org/notes.txt;token=hunter2;mime=text/plain;size=5\r\n");
+/// ```
+#[derive(Clone)]
+pub struct TitanRequest {
+    url: String,
+    token: Option<String>,
+    mime: Option<String>,
+    data: Vec<u8>,
+}
+
+impl TitanRequest {
+    /// Creates a request that uploads `data` to `url`, which must start with the scheme
+    /// `"titan://"`.
+    #[must_use]
+    pub fn new(url: String, data: Vec<u8>) -> TitanRequest {
+        TitanRequest {
+            url,
+            token: None,
+            mime: None,
+            data,
+        }
+    }
+
+    /// Sets the authentication token the server requires for uploads.
+    #[must_use]
+    pub fn token(mut self, token: String) -> TitanRequest {
+        self.token = Some(token);
+        self
+    }
+
+    /// Sets the MIME type of the uploaded data. Servers assume `text/gemini` if none is given.
+    #[must_use]
+    pub fn mime(mut self, mime: String) -> TitanRequest {
+        self.mime = Some(mime);
+        self
+    }
+
+    /// Returns the URL the data will be uploaded to.
+    #[must_use]
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Returns the data that will be uploaded.
+    #[must_use]
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Returns the request line sent to the server before the data, including the trailing
+    /// `<CR><LF>`.
+    ///
+    /// The parameters are part of the path, so they go after it and before any query or
+    /// fragment.
+    #[must_use]
+    pub fn request_line(&self) -> String {
+        let path_end = self.url.find(['?', '#']).unwrap_or(self.url.len());
+        let (path, rest) = self.url.split_at(path_end);
+        let mut line = path.to_string();
+        if let Some(token) = &self.token {
+            line += ";token=";
+            line.extend(utf8_percent_encode(token, PARAMETER));
+        }
+        if let Some(mime) = &self.mime {
+            line += ";mime=";
+            line.extend(utf8_percent_encode(mime, PARAMETER));
+        }
+        line += &format!(";size={}{}\r\n", self.data.len(), rest);
+
+        line
+    }
+}
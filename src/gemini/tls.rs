@@ -0,0 +1,546 @@
+use std::net::TcpStream;
+use std::sync::{Arc, Mutex};
+
+use super::known_hosts::KnownHosts;
+use super::transport::Connection;
+use super::Error;
+
+#[cfg(feature = "rustls")]
+pub use self::rustls_backend::RustlsConnector;
+
+#[cfg(feature = "native-tls")]
+pub use self::native_tls_backend::NativeTlsConnector;
+
+/// Represents a TLS implementation that can secure a TCP stream to a gemini server.
+///
+/// The `rustls` feature, enabled by default, provides [`RustlsConnector`] and the `native-tls`
+/// feature provides `NativeTlsConnector`, which uses the platform's TLS library.
+pub trait Connector: Send + Sync {
+    /// Performs a TLS handshake over `stream` to `host`, which is in the form `<host>:<port>`,
+    /// expecting the server to identify as `server_name`.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if the handshake couldn't be started or failed.
+    fn connect(
+        &self,
+        host: &str,
+        server_name: &str,
+        stream: TcpStream,
+    ) -> Result<Box<dyn Connection>, Error>;
+
+    /// Returns the connector as a [`RustlsConnector`] if it is one. rustls is the only TLS
+    /// implementation [`super::Client::async_request`] can drive, so it fails for connectors that
+    /// return `None`, which is the default.
+    #[cfg(feature = "async")]
+    fn as_rustls(&self) -> Option<&RustlsConnector> {
+        None
+    }
+}
+
+/// Represents the ways a connector can decide whether to trust the certificate a server presents.
+#[derive(Clone, Default)]
+pub enum CertificatePolicy {
+    /// Accept any certificate. This is the default, as most gemini servers use self-signed
+    /// certificates.
+    #[default]
+    AcceptAll,
+    /// Trust the first certificate each host presents and refuse any other certificate from it
+    /// afterwards, see [`KnownHosts`].
+    TrustOnFirstUse(Arc<Mutex<KnownHosts>>),
+    /// Only accept certificates issued for the server name by a certificate authority. rustls
+    /// uses a bundled copy of Mozilla's root certificates, `native-tls` uses the platform's.
+    WebPki,
+}
+
+/// Returns the connector a client uses unless told otherwise, preferring rustls when both
+/// backends are enabled.
+#[cfg(feature = "rustls")]
+pub(super) fn default_connector() -> Result<Arc<dyn Connector>, Error> {
+    Ok(Arc::new(RustlsConnector::new()))
+}
+
+/// Returns the connector a client uses unless told otherwise, preferring rustls when both
+/// backends are enabled.
+#[cfg(all(feature = "native-tls", not(feature = "rustls")))]
+pub(super) fn default_connector() -> Result<Arc<dyn Connector>, Error> {
+    Ok(Arc::new(NativeTlsConnector::new()?))
+}
+
+/// Returns the connector a client uses unless told otherwise. Without a TLS backend there's
+/// nothing to secure connections with, so it fails every handshake, and clients need to be given
+/// a connector or transport of their own.
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+pub(super) fn default_connector() -> Result<Arc<dyn Connector>, Error> {
+    Ok(Arc::new(NoBackend))
+}
+
+/// The connector used when neither the `rustls` nor the `native-tls` feature is enabled.
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+struct NoBackend;
+
+#[cfg(not(any(feature = "rustls", feature = "native-tls")))]
+impl Connector for NoBackend {
+    fn connect(
+        &self,
+        _host: &str,
+        _server_name: &str,
+        _stream: TcpStream,
+    ) -> Result<Box<dyn Connection>, Error> {
+        Err(Error::StreamIO(
+            "No TLS backend is enabled, set a connector or transport",
+            std::io::ErrorKind::Unsupported.into(),
+        ))
+    }
+}
+
+#[cfg(feature = "rustls")]
+mod rustls_backend {
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{CertificatePolicy, Connection, Connector};
+    use crate::gemini::known_hosts::KnownHosts;
+    use crate::gemini::Error;
+
+    use rustls::client::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+
+    struct NoCertVerification;
+
+    impl ServerCertVerifier for NoCertVerification {
+        fn verify_server_cert(
+            &self,
+            _end_entity: &rustls::Certificate,
+            _intermediates: &[rustls::Certificate],
+            _server_name: &rustls::ServerName,
+            _scts: &mut dyn Iterator<Item = &[u8]>,
+            _ocsp_response: &[u8],
+            _now: std::time::SystemTime,
+        ) -> Result<rustls::client::ServerCertVerified, rustls::Error> {
+            Ok(rustls::client::ServerCertVerified::assertion())
+        }
+    }
+
+    /// Notes whether the server's certificate was verified, otherwise verifying it as `inner`
+    /// does. rustls doesn't verify the certificate again when a session is resumed, which is the
+    /// only way it lets a client tell that it was.
+    struct RecordingVerifier {
+        inner: Arc<dyn ServerCertVerifier>,
+        verified: Arc<AtomicBool>,
+    }
+
+    impl ServerCertVerifier for RecordingVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &rustls::Certificate,
+            intermediates: &[rustls::Certificate],
+            server_name: &rustls::ServerName,
+            scts: &mut dyn Iterator<Item = &[u8]>,
+            ocsp_response: &[u8],
+            now: std::time::SystemTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            self.verified.store(true, Ordering::Relaxed);
+            self.inner.verify_server_cert(
+                end_entity,
+                intermediates,
+                server_name,
+                scts,
+                ocsp_response,
+                now,
+            )
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &rustls::Certificate,
+            dss: &rustls::DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.inner.verify_tls12_signature(message, cert, dss)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &rustls::Certificate,
+            dss: &rustls::DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.inner.verify_tls13_signature(message, cert, dss)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+            self.inner.supported_verify_schemes()
+        }
+
+        fn request_scts(&self) -> bool {
+            self.inner.request_scts()
+        }
+    }
+
+    /// A [`Connector`] backed by rustls.
+    #[derive(Clone)]
+    pub struct RustlsConnector {
+        config: Arc<rustls::ClientConfig>,
+        known_hosts: Option<Arc<Mutex<KnownHosts>>>,
+        /// The verifier `config` was built with, if it's known, so connections can tell whether
+        /// it was used.
+        verifier: Option<Arc<dyn ServerCertVerifier>>,
+    }
+
+    /// A TLS connection to a gemini server over TCP.
+    struct TlsConnection {
+        conn: rustls::ClientConnection,
+        stream: TcpStream,
+        /// Whether the server's certificate was verified, if the connector could tell.
+        verified: Option<Arc<AtomicBool>>,
+    }
+
+    impl RustlsConnector {
+        /// Creates a connector that accepts any certificate the server presents, which is what
+        /// most gemini servers expect as they use self-signed certificates.
+        #[must_use]
+        pub fn new() -> RustlsConnector {
+            Self::with_policy(CertificatePolicy::AcceptAll)
+        }
+
+        /// Creates a connector that decides whether to trust certificates according to
+        /// `policy`.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use leda::gemini::{Client, tls::{CertificatePolicy, RustlsConnector}};
+        /// use std::sync::Arc;
+        ///
+        /// let mut client = Client::new().unwrap();
+        /// client.set_connector(Arc::new(RustlsConnector::with_policy(CertificatePolicy::WebPki)));
+        /// ```
+        #[must_use]
+        pub fn with_policy(policy: CertificatePolicy) -> RustlsConnector {
+            let (verifier, known_hosts): (Arc<dyn ServerCertVerifier>, _) = match policy {
+                CertificatePolicy::AcceptAll => (Arc::new(NoCertVerification), None),
+                CertificatePolicy::TrustOnFirstUse(known_hosts) => {
+                    (Arc::new(NoCertVerification), Some(known_hosts))
+                }
+                CertificatePolicy::WebPki => {
+                    let mut roots = rustls::RootCertStore::empty();
+                    roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(
+                        |anchor| {
+                            rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                                anchor.subject,
+                                anchor.spki,
+                                anchor.name_constraints,
+                            )
+                        },
+                    ));
+                    (
+                        Arc::new(rustls::client::WebPkiVerifier::new(roots, None)),
+                        None,
+                    )
+                }
+            };
+            let config = rustls::ClientConfig::builder()
+                .with_safe_defaults()
+                .with_custom_certificate_verifier(verifier.clone())
+                .with_no_client_auth();
+
+            RustlsConnector {
+                config: Arc::new(config),
+                known_hosts,
+                verifier: Some(verifier),
+            }
+        }
+
+        /// Creates a connector using the given rustls configuration.
+        ///
+        /// Connections made with it can't tell whether their session was resumed, see
+        /// [`crate::gemini::ConnectionInfo::session_resumed`].
+        #[must_use]
+        pub fn with_config(config: Arc<rustls::ClientConfig>) -> RustlsConnector {
+            RustlsConnector {
+                config,
+                known_hosts: None,
+                verifier: None,
+            }
+        }
+
+        /// Creates a connector that trusts the first certificate each host presents and refuses
+        /// connections to hosts presenting any other certificate afterwards, see [`KnownHosts`].
+        ///
+        /// Newly seen hosts are added to `known_hosts`, which the caller is responsible for
+        /// saving.
+        #[must_use]
+        pub fn with_known_hosts(known_hosts: Arc<Mutex<KnownHosts>>) -> RustlsConnector {
+            Self::with_policy(CertificatePolicy::TrustOnFirstUse(known_hosts))
+        }
+
+        /// Sets how many hosts' TLS sessions are remembered so later connections to them can
+        /// resume the session instead of making a full handshake. The least recently used are
+        /// forgotten first. A `capacity` of 0 turns resumption off.
+        ///
+        /// Connectors remember 256 hosts' sessions by default. The sessions are shared by every
+        /// client using the connector, clones of a client included.
+        ///
+        /// # Examples
+        ///
+        /// ```
+        /// use leda::gemini::{Client, tls::RustlsConnector};
+        /// use std::sync::Arc;
+        ///
+        /// let mut connector = RustlsConnector::new();
+        /// connector.set_session_cache(1024);
+        /// let mut client = Client::new().unwrap();
+        /// client.set_connector(Arc::new(connector));
+        /// ```
+        pub fn set_session_cache(&mut self, capacity: usize) {
+            let config = Arc::make_mut(&mut self.config);
+            config.session_storage = if capacity == 0 {
+                Arc::new(rustls::client::NoClientSessionStorage {})
+            } else {
+                rustls::client::ClientSessionMemoryCache::new(capacity)
+            };
+        }
+
+        /// Returns the rustls configuration connections are made with.
+        #[must_use]
+        pub fn config(&self) -> &Arc<rustls::ClientConfig> {
+            &self.config
+        }
+
+        /// Checks the certificate the server presented on `conn`, whose handshake must be done,
+        /// against the known hosts if the connector trusts on first use.
+        pub(crate) fn check_known_host(
+            &self,
+            host: &str,
+            conn: &rustls::ClientConnection,
+        ) -> Result<(), Error> {
+            let Some(known_hosts) = &self.known_hosts else {
+                return Ok(());
+            };
+
+            let certificate = conn
+                .peer_certificates()
+                .and_then(|certificates| certificates.first())
+                .ok_or(Error::TLSClient(rustls::Error::NoCertificatesPresented))?;
+            known_hosts.lock().unwrap().check(host, &certificate.0)
+        }
+    }
+
+    impl Default for RustlsConnector {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl Connector for RustlsConnector {
+        fn connect(
+            &self,
+            host: &str,
+            server_name: &str,
+            mut stream: TcpStream,
+        ) -> Result<Box<dyn Connection>, Error> {
+            let rustls_server_name = server_name
+                .try_into()
+                .map_err(|_| Error::ServerName(server_name.to_string()))?;
+            // Each connection gets its own copy of the configuration, sharing everything but a
+            // verifier that notes whether it was used.
+            let (config, verified) = match &self.verifier {
+                Some(verifier) => {
+                    let verified = Arc::new(AtomicBool::new(false));
+                    let mut config = rustls::ClientConfig::clone(&self.config);
+                    config
+                        .dangerous()
+                        .set_certificate_verifier(Arc::new(RecordingVerifier {
+                            inner: verifier.clone(),
+                            verified: verified.clone(),
+                        }));
+                    (Arc::new(config), Some(verified))
+                }
+                None => (self.config.clone(), None),
+            };
+            let mut conn = rustls::ClientConnection::new(config, rustls_server_name)?;
+
+            // The certificate is only known once the handshake is done, so it has to be finished
+            // here rather than when the request is sent to check it before sending anything.
+            if self.known_hosts.is_some() {
+                while conn.is_handshaking() {
+                    conn.complete_io(&mut stream)
+                        .map_err(|e| Error::StreamIO("TLS handshake failed", e))?;
+                }
+                self.check_known_host(host, &conn)?;
+            }
+
+            Ok(Box::new(TlsConnection {
+                conn,
+                stream,
+                verified,
+            }))
+        }
+
+        #[cfg(feature = "async")]
+        fn as_rustls(&self) -> Option<&RustlsConnector> {
+            Some(self)
+        }
+    }
+
+    impl Read for TlsConnection {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            rustls::Stream::new(&mut self.conn, &mut self.stream).read(buf)
+        }
+    }
+
+    impl Write for TlsConnection {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            rustls::Stream::new(&mut self.conn, &mut self.stream).write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            rustls::Stream::new(&mut self.conn, &mut self.stream).flush()
+        }
+    }
+
+    impl Connection for TlsConnection {
+        fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+            self.stream.set_read_timeout(timeout)
+        }
+
+        fn shutdown(&mut self) -> std::io::Result<()> {
+            self.conn.send_close_notify();
+            while self.conn.wants_write() {
+                self.conn.write_tls(&mut self.stream)?;
+            }
+            self.stream.flush()
+        }
+
+        fn tls_version(&self) -> Option<&'static str> {
+            match self.conn.protocol_version()? {
+                rustls::ProtocolVersion::TLSv1_2 => Some("TLSv1.2"),
+                rustls::ProtocolVersion::TLSv1_3 => Some("TLSv1.3"),
+                _ => None,
+            }
+        }
+
+        fn session_resumed(&self) -> Option<bool> {
+            if self.conn.is_handshaking() {
+                return None;
+            }
+            let verified = self.verified.as_ref()?;
+            Some(!verified.load(Ordering::Relaxed))
+        }
+    }
+}
+
+#[cfg(feature = "native-tls")]
+mod native_tls_backend {
+    use std::net::TcpStream;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use super::{CertificatePolicy, Connection, Connector};
+    use crate::gemini::known_hosts::KnownHosts;
+    use crate::gemini::Error;
+
+    /// A [`Connector`] backed by the platform's TLS library through `native-tls`.
+    #[derive(Clone)]
+    pub struct NativeTlsConnector {
+        connector: native_tls::TlsConnector,
+        known_hosts: Option<Arc<Mutex<KnownHosts>>>,
+    }
+
+    impl NativeTlsConnector {
+        /// Creates a connector that accepts any certificate the server presents, which is what
+        /// most gemini servers expect as they use self-signed certificates.
+        ///
+        /// # Errors
+        ///
+        /// Will return an [`Error::NativeTLS`] if the platform's TLS library couldn't be
+        /// initialized.
+        pub fn new() -> Result<NativeTlsConnector, Error> {
+            Self::with_policy(CertificatePolicy::AcceptAll)
+        }
+
+        /// Creates a connector that decides whether to trust certificates according to
+        /// `policy`.
+        ///
+        /// # Errors
+        ///
+        /// Will return an [`Error::NativeTLS`] if the platform's TLS library couldn't be
+        /// initialized.
+        pub fn with_policy(policy: CertificatePolicy) -> Result<NativeTlsConnector, Error> {
+            let mut builder = native_tls::TlsConnector::builder();
+            if !matches!(policy, CertificatePolicy::WebPki) {
+                builder
+                    .danger_accept_invalid_certs(true)
+                    .danger_accept_invalid_hostnames(true);
+            }
+            let connector = builder.build()?;
+
+            let known_hosts = match policy {
+                CertificatePolicy::TrustOnFirstUse(known_hosts) => Some(known_hosts),
+                _ => None,
+            };
+
+            Ok(NativeTlsConnector {
+                connector,
+                known_hosts,
+            })
+        }
+
+        /// Creates a connector using the given `native-tls` connector.
+        #[must_use]
+        pub fn with_connector(connector: native_tls::TlsConnector) -> NativeTlsConnector {
+            NativeTlsConnector {
+                connector,
+                known_hosts: None,
+            }
+        }
+    }
+
+    impl Connector for NativeTlsConnector {
+        fn connect(
+            &self,
+            host: &str,
+            server_name: &str,
+            stream: TcpStream,
+        ) -> Result<Box<dyn Connection>, Error> {
+            let stream = match self.connector.connect(server_name, stream) {
+                Ok(stream) => stream,
+                Err(native_tls::HandshakeError::Failure(e)) => return Err(Error::NativeTLS(e)),
+                Err(native_tls::HandshakeError::WouldBlock(_)) => {
+                    return Err(Error::StreamIO(
+                        "TLS handshake was interrupted",
+                        std::io::ErrorKind::WouldBlock.into(),
+                    ))
+                }
+            };
+
+            if let Some(known_hosts) = &self.known_hosts {
+                let certificate = stream
+                    .peer_certificate()?
+                    .ok_or_else(|| {
+                        Error::StreamIO(
+                            "TLS handshake failed",
+                            std::io::Error::other("the server presented no certificate"),
+                        )
+                    })?
+                    .to_der()?;
+                known_hosts.lock().unwrap().check(host, &certificate)?;
+            }
+
+            Ok(Box::new(stream))
+        }
+    }
+
+    impl Connection for native_tls::TlsStream<TcpStream> {
+        fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+            self.get_ref().set_read_timeout(timeout)
+        }
+
+        fn shutdown(&mut self) -> std::io::Result<()> {
+            native_tls::TlsStream::shutdown(self)
+        }
+    }
+}
@@ -0,0 +1,284 @@
+use std::fs::File;
+use std::io::{Cursor, ErrorKind, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use super::tls::Connector;
+use super::Error;
+use crate::net::{self, ConnectError};
+
+/// Represents an open connection to a server that a request can be sent over.
+pub trait Connection: Read + Write {
+    /// Sets how long a read may wait for the server before failing, or lets reads wait forever if
+    /// `timeout` is `None`. Connections that can't time out ignore this, which is the default.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`std::io::Error`] if the timeout couldn't be set.
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        let _ = timeout;
+        Ok(())
+    }
+
+    /// Tells the server the connection is being closed on purpose, such as by sending a TLS
+    /// `close_notify`. Connections without a way to do so do nothing, which is the default.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`std::io::Error`] if the server couldn't be told.
+    fn shutdown(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    /// Returns the TLS version negotiated for the connection, such as `"TLSv1.3"`, if it's known.
+    /// Connections that don't know it return `None`, which is the default.
+    fn tls_version(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns whether the TLS session was resumed from an earlier connection, skipping a full
+    /// handshake, once the handshake is done. Connections that can't tell return `None`, which
+    /// is the default.
+    fn session_resumed(&self) -> Option<bool> {
+        None
+    }
+}
+
+/// Represents a way of opening connections to gemini servers.
+///
+/// A [`super::Client`] uses TLS over TCP unless it's given a different transport with
+/// [`super::Client::set_transport`], which lets the request and parsing logic be exercised
+/// without a live server.
+pub trait Transport: Send + Sync {
+    /// Opens a connection to `host`, which is in the form `<host>:<port>`. `server_name` is the
+    /// name the server is expected to identify itself as.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if the connection couldn't be opened.
+    fn connect(&self, host: &str, server_name: &str) -> Result<Box<dyn Connection>, Error>;
+}
+
+/// A transport that answers every connection with the same canned response, and records what
+/// was sent over each connection.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::{Client, transport::MemoryTransport};
+/// use std::sync::Arc;
+///
+/// let transport = MemoryTransport::new("20 text/gemini\r\n# Hello!\n");
+/// let mut client = Client::new().unwrap();
+/// client.set_transport(Some(Arc::new(transport.clone())));
+///
+/// let response = client.request(String::from("gemini://example.org/")).unwrap();
+/// assert_eq!(response.body.unwrap(), b"# Hello!\n");
+/// assert_eq!(transport.requests(), [b"gemini://example.org/\r\n".to_vec()]);
+/// ```
+#[derive(Clone)]
+pub struct MemoryTransport {
+    response: Vec<u8>,
+    requests: Arc<Mutex<Vec<Vec<u8>>>>,
+    unclean_eof: bool,
+}
+
+/// A connection opened by a [`MemoryTransport`].
+struct MemoryConnection {
+    response: Cursor<Vec<u8>>,
+    requests: Arc<Mutex<Vec<Vec<u8>>>>,
+    index: usize,
+    unclean_eof: bool,
+}
+
+impl MemoryTransport {
+    /// Creates a transport whose connections all respond with `response`.
+    pub fn new(response: impl Into<Vec<u8>>) -> MemoryTransport {
+        MemoryTransport {
+            response: response.into(),
+            requests: Arc::new(Mutex::new(Vec::new())),
+            unclean_eof: false,
+        }
+    }
+
+    /// Creates a transport whose connections all respond with the contents of the file at
+    /// `path`, such as a response captured by a [`RecordingTransport`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`std::io::Error`] if the file couldn't be read.
+    pub fn from_file(path: impl AsRef<Path>) -> std::io::Result<MemoryTransport> {
+        Ok(Self::new(std::fs::read(path)?))
+    }
+
+    /// Makes connections end with an [`std::io::ErrorKind::UnexpectedEof`] error once the
+    /// response has been read, the way a TLS connection closed without a `close_notify` does.
+    #[must_use]
+    pub fn without_close_notify(mut self) -> MemoryTransport {
+        self.unclean_eof = true;
+        self
+    }
+
+    /// Returns the bytes sent over each connection opened so far, in the order they were opened.
+    #[must_use]
+    pub fn requests(&self) -> Vec<Vec<u8>> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn connect(&self, _host: &str, _server_name: &str) -> Result<Box<dyn Connection>, Error> {
+        let mut requests = self.requests.lock().unwrap();
+        requests.push(Vec::new());
+
+        Ok(Box::new(MemoryConnection {
+            response: Cursor::new(self.response.clone()),
+            requests: self.requests.clone(),
+            index: requests.len() - 1,
+            unclean_eof: self.unclean_eof,
+        }))
+    }
+}
+
+impl Read for MemoryConnection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self.response.read(buf)? {
+            0 if self.unclean_eof && !buf.is_empty() => Err(ErrorKind::UnexpectedEof.into()),
+            read => Ok(read),
+        }
+    }
+}
+
+impl Write for MemoryConnection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.requests.lock().unwrap()[self.index].extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Connection for MemoryConnection {}
+
+/// A transport that connects over TLS, the same way a [`super::Client`] does when it isn't given
+/// a transport. It's mostly useful to wrap in another transport, such as a
+/// [`RecordingTransport`].
+pub struct TlsTransport {
+    connector: Arc<dyn Connector>,
+    timeout: Option<Duration>,
+}
+
+impl TlsTransport {
+    /// Creates a transport that secures its connections with `connector`, giving up on
+    /// connecting after `timeout` if there is one.
+    #[must_use]
+    pub fn new(connector: Arc<dyn Connector>, timeout: Option<Duration>) -> TlsTransport {
+        TlsTransport { connector, timeout }
+    }
+}
+
+impl Transport for TlsTransport {
+    fn connect(&self, host: &str, server_name: &str) -> Result<Box<dyn Connection>, Error> {
+        let stream = net::connect(host, self.timeout).map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(host.to_string()),
+            ConnectError::Io(e) => Error::TCPConnect(e, host.to_string()),
+        })?;
+
+        self.connector.connect(host, server_name, stream)
+    }
+}
+
+/// A transport that writes the exact bytes each server responds with to a file, so a real
+/// session can be replayed later with [`MemoryTransport::from_file`].
+///
+/// Connections are opened with the wrapped transport, and every connection overwrites the file,
+/// so it holds the last response received.
+///
+/// # Examples
+///
+/// ```no_run
+/// # #[cfg(feature = "rustls")]
+/// # fn main() {
+/// use leda::gemini::{Client, tls::RustlsConnector, transport::{RecordingTransport, TlsTransport}};
+/// use std::sync::Arc;
+///
+/// let tls = TlsTransport::new(Arc::new(RustlsConnector::new()), None);
+/// let mut client = Client::new().unwrap();
+/// client.set_transport(Some(Arc::new(RecordingTransport::new(Arc::new(tls), "capture.gmi"))));
+/// client.request(String::from("gemini://example.org/")).unwrap();
+/// # }
+/// # #[cfg(not(feature = "rustls"))]
+/// # fn main() {}
+/// ```
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    path: PathBuf,
+}
+
+/// A connection opened by a [`RecordingTransport`].
+struct RecordingConnection {
+    inner: Box<dyn Connection>,
+    file: File,
+}
+
+impl RecordingTransport {
+    /// Creates a transport that opens connections with `inner` and records their responses to
+    /// the file at `path`.
+    pub fn new(inner: Arc<dyn Transport>, path: impl Into<PathBuf>) -> RecordingTransport {
+        RecordingTransport {
+            inner,
+            path: path.into(),
+        }
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn connect(&self, host: &str, server_name: &str) -> Result<Box<dyn Connection>, Error> {
+        let file = File::create(&self.path)
+            .map_err(|e| Error::StreamIO("Failed to create recording file", e))?;
+
+        Ok(Box::new(RecordingConnection {
+            inner: self.inner.connect(host, server_name)?,
+            file,
+        }))
+    }
+}
+
+impl Read for RecordingConnection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.file.write_all(&buf[..read])?;
+        Ok(read)
+    }
+}
+
+impl Write for RecordingConnection {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl Connection for RecordingConnection {
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+        self.inner.set_read_timeout(timeout)
+    }
+
+    fn shutdown(&mut self) -> std::io::Result<()> {
+        self.inner.shutdown()
+    }
+
+    fn tls_version(&self) -> Option<&'static str> {
+        self.inner.tls_version()
+    }
+
+    fn session_resumed(&self) -> Option<bool> {
+        self.inner.session_resumed()
+    }
+}
@@ -0,0 +1,190 @@
+use std::fmt;
+use std::str::FromStr;
+
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+
+use super::{Error, DEFAULT_PORT};
+
+/// Characters that are percent-encoded in user input, everything but RFC 3986's unreserved
+/// characters.
+const INPUT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// Builds the URL to request after a server asked for input with an
+/// [`super::header::StatusCode::Input`] response.
+///
+/// `input` is percent-encoded and replaces the query of `url`, which should be the URL that
+/// responded asking for input. Any fragment is dropped since it's never sent to the server.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::urls;
+///
+/// let url = urls::input_url("gemini://example.org/search", "café & crêpes = 2").unwrap();
+/// assert_eq!(url, "gemini://example.org/search?caf%C3%A9%20%26%20cr%C3%AApes%20%3D%202");
+/// ```
+///
+/// # Errors
+///
+/// Will return an [`Error::UrlParse`] if `url` isn't a valid URL.
+pub fn input_url(url: &str, input: &str) -> Result<String, Error> {
+    let mut url = url::Url::parse(url)?;
+    let query: String = utf8_percent_encode(input, INPUT).collect();
+    url.set_query(Some(&query));
+    url.set_fragment(None);
+
+    Ok(url.to_string())
+}
+
+/// Puts `url` in a canonical form, so URLs that point to the same resource compare equal and
+/// can be used to deduplicate requests.
+///
+/// Exactly these normalizations are applied:
+///
+/// - The scheme and host are lowercased.
+/// - A trailing dot is removed from the host, `example.org.` becomes `example.org`.
+/// - The port is removed from gemini URLs if it's the default, [`DEFAULT_PORT`].
+/// - `.` and `..` path segments are resolved.
+/// - An empty path becomes `/`.
+/// - The hex digits of percent-encoded characters are uppercased, `%c3%a9` becomes `%C3%A9`.
+/// - The fragment is dropped, it's never sent to the server.
+///
+/// Percent-encoded characters aren't decoded, and the query is otherwise left as it is.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::urls;
+///
+/// let url = urls::normalize_url("GEMINI://Example.ORG.:1965/a/./b/../caf%c3%a9?q#top").unwrap();
+/// assert_eq!(url, "gemini://example.org/a/caf%C3%A9?q");
+/// ```
+///
+/// # Errors
+///
+/// Will return an [`Error::UrlParse`] if `url` isn't a valid URL.
+pub fn normalize_url(url: &str) -> Result<String, Error> {
+    let url = url::Url::parse(url)?;
+    Ok(normalize(url).to_string())
+}
+
+/// Represents a URL in the canonical form [`normalize_url`] describes, so URLs that point to
+/// the same resource are equal, hash the same, and sort next to each other. This makes it
+/// suitable as the key of a crawler's set of visited pages, whether a `HashSet` or a
+/// `BTreeSet`.
+///
+/// URLs are ordered by their normalized text.
+///
+/// # Examples
+///
+/// ```
+/// use leda::gemini::urls::CanonicalUrl;
+/// use std::collections::BTreeSet;
+///
+/// let mut visited = BTreeSet::new();
+/// visited.insert("gemini://example.org/b".parse::<CanonicalUrl>().unwrap());
+/// visited.insert("gemini://EXAMPLE.org:1965/a/../b#top".parse().unwrap());
+/// visited.insert("gemini://example.org/a".parse().unwrap());
+///
+/// let urls: Vec<_> = visited.iter().map(CanonicalUrl::as_str).collect();
+/// assert_eq!(urls, ["gemini://example.org/a", "gemini://example.org/b"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CanonicalUrl(url::Url);
+
+impl CanonicalUrl {
+    /// Creates a canonical URL by normalizing `url`.
+    #[must_use]
+    pub fn new(url: url::Url) -> CanonicalUrl {
+        CanonicalUrl(normalize(url))
+    }
+
+    /// Returns the normalized URL.
+    #[must_use]
+    pub fn as_url(&self) -> &url::Url {
+        &self.0
+    }
+
+    /// Returns the normalized URL as a string.
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Consumes the canonical URL, returning the normalized URL.
+    #[must_use]
+    pub fn into_url(self) -> url::Url {
+        self.0
+    }
+}
+
+impl From<url::Url> for CanonicalUrl {
+    fn from(url: url::Url) -> CanonicalUrl {
+        CanonicalUrl::new(url)
+    }
+}
+
+impl FromStr for CanonicalUrl {
+    type Err = Error;
+
+    /// Parses and normalizes a URL, see [`normalize_url`].
+    fn from_str(url: &str) -> Result<CanonicalUrl, Error> {
+        Ok(CanonicalUrl::new(url::Url::parse(url)?))
+    }
+}
+
+impl fmt::Display for CanonicalUrl {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Applies the normalizations [`normalize_url`] describes to an already parsed URL. Parsing
+/// lowercases the scheme and resolves dot segments, the rest is done here.
+pub(crate) fn normalize(mut url: url::Url) -> url::Url {
+    url.set_fragment(None);
+    if url.scheme() == "gemini" && url.port() == Some(DEFAULT_PORT) {
+        let _ = url.set_port(None);
+    }
+    if let Some(host) = url.host_str() {
+        let normalized = host.trim_end_matches('.').to_ascii_lowercase();
+        if normalized != host && !normalized.is_empty() {
+            let _ = url.set_host(Some(&normalized));
+        }
+        if url.path().is_empty() {
+            url.set_path("/");
+        }
+    }
+
+    let path = uppercase_escapes(url.path());
+    url.set_path(&path);
+    if let Some(query) = url.query() {
+        let query = uppercase_escapes(query);
+        url.set_query(Some(&query));
+    }
+
+    url
+}
+
+/// Uppercases the hex digits of every percent-encoded character in `text`.
+fn uppercase_escapes(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.char_indices();
+    while let Some((index, c)) = chars.next() {
+        match text.get(index + 1..index + 3) {
+            Some(hex) if c == '%' && hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+                output.push('%');
+                output += &hex.to_ascii_uppercase();
+                // Skip the two digits.
+                chars.nth(1);
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
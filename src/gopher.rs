@@ -0,0 +1,35 @@
+//! A module with all the gopher protocol functionality.
+
+/// Make gopher requests.
+mod client;
+/// Represent and parse gopher menus.
+pub mod menu;
+/// Render gopher menus as gemtext and HTML.
+mod render;
+
+pub use client::Client;
+pub use menu::{GopherItem, ItemType, Menu};
+
+use thiserror::Error;
+
+/// The port gopher servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 70;
+
+/// Represents the different error types this module returns
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to parse URL: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("The given URL didn't use the gopher scheme: {0}")]
+    UrlScheme(String),
+    #[error("The given URL didn't have a host: {0}")]
+    UrlNoHost(String),
+    #[error("The URL couldn't be resolved to an address: {0}")]
+    UrlNoAddress(String),
+    #[error("Couldn't connect to address {1}, TCP connection error: {0}")]
+    TCPConnect(std::io::Error, String),
+    #[error("Stream IO failure, {0}: {1}")]
+    StreamIO(&'static str, std::io::Error),
+    #[error("Malformed gopher menu: {0}")]
+    MenuFormat(String),
+}
@@ -0,0 +1,177 @@
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use super::menu::{GopherItem, Menu};
+use super::Error;
+use crate::net::{self, ConnectError};
+
+/// Represents a client which will make gopher requests.
+pub struct Client {
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a client that can be used to make gopher requests
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::gopher::Client;
+    ///
+    /// let client = Client::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Client {
+        Self::with_timeout(None)
+    }
+
+    /// Creates a client that can be used to make gopher requests with a timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::gopher::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::with_timeout(Some(Duration::new(5, 0)));
+    /// ```
+    #[must_use]
+    pub fn with_timeout(timeout: Option<Duration>) -> Client {
+        Client { timeout }
+    }
+
+    /// Sets the timeout for the client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gopher::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new();
+    /// // A timeout of 5 seconds
+    /// client.set_timeout(Some(Duration::from_secs(5)));
+    /// // No timeout
+    /// client.set_timeout(None);
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sends `selector` to `host` on `port` and returns the raw response.
+    ///
+    /// Menus can be parsed from the response with [`super::Menu::new`], other item types are
+    /// returned as is.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::gopher::{self, Client, Menu};
+    ///
+    /// let client = Client::new();
+    /// let response = client.request("gopher.floodgap.com", gopher::DEFAULT_PORT, "")
+    ///     .expect("Failed to retrieve gopher menu");
+    /// let menu = Menu::new(&String::from_utf8_lossy(&response));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server.
+    pub fn request(&self, host: &str, port: u16, selector: &str) -> Result<Vec<u8>, Error> {
+        self.send(host, port, &format!("{}\r\n", selector))
+    }
+
+    /// Sends `query` to the search server at `selector` on `host` and `port`, and parses the
+    /// menu of results it responds with.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::gopher::{self, Client};
+    ///
+    /// let client = Client::new();
+    /// let results = client.search("gopher.floodgap.com", gopher::DEFAULT_PORT, "/v2/vs", "leda")
+    ///     .expect("Failed to search");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server,
+    /// or an [`Error::MenuFormat`] if the results aren't a valid menu.
+    pub fn search(
+        &self,
+        host: &str,
+        port: u16,
+        selector: &str,
+        query: &str,
+    ) -> Result<Menu, Error> {
+        let response = self.send(host, port, &format!("{}\t{}\r\n", selector, query))?;
+        Menu::new(&String::from_utf8_lossy(&response))
+    }
+
+    /// Sends `request` to `host` on `port` and returns the raw response.
+    fn send(&self, host: &str, port: u16, request: &str) -> Result<Vec<u8>, Error> {
+        let host = format!("{}:{}", host, port);
+
+        let mut stream = net::connect(&host, self.timeout).map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(host.clone()),
+            ConnectError::Io(e) => Error::TCPConnect(e, host.clone()),
+        })?;
+
+        stream
+            .write_all(request.as_bytes())
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+
+        Ok(response)
+    }
+
+    /// Retrieves the resource a [`GopherItem`] points to.
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server.
+    pub fn request_item(&self, item: &GopherItem) -> Result<Vec<u8>, Error> {
+        self.request(&item.host, item.port, &item.selector)
+    }
+
+    /// Searches the search server a [`GopherItem`] points to for `query`, see
+    /// [`Client::search`].
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server,
+    /// or an [`Error::MenuFormat`] if the results aren't a valid menu.
+    pub fn search_item(&self, item: &GopherItem, query: &str) -> Result<Menu, Error> {
+        self.search(&item.host, item.port, &item.selector, query)
+    }
+
+    /// Retrieves the resource a `gopher://` URL points to.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::gopher::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request_url("gopher://gopher.floodgap.com/1/world");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url or
+    /// communicating with the server.
+    pub fn request_url(&self, url: &str) -> Result<Vec<u8>, Error> {
+        self.request_item(&GopherItem::from_url(url)?)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,261 @@
+use percent_encoding::percent_decode_str;
+
+use super::{Error, DEFAULT_PORT};
+use crate::net;
+
+/// Represents a gopher menu, item by item.
+#[derive(Debug, PartialEq)]
+pub struct Menu {
+    /// List of items.
+    pub items: Vec<GopherItem>,
+}
+
+/// Represents a single line of a gopher menu.
+#[derive(Debug, PartialEq, Clone)]
+pub struct GopherItem {
+    /// What kind of resource this item points to.
+    pub item_type: ItemType,
+    /// The human readable text to display for this item.
+    pub display: String,
+    /// The selector to send to the server to retrieve this item.
+    pub selector: String,
+    /// The host the item lives on.
+    pub host: String,
+    /// The port the item's host listens on.
+    pub port: u16,
+}
+
+/// Represents the different item types a gopher menu can link to.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ItemType {
+    /// `0`, a plain text file.
+    TextFile,
+    /// `1`, another gopher menu.
+    Directory,
+    /// `2`, a CSO phone-book server.
+    CsoPhoneBook,
+    /// `3`, an error message from the server.
+    Error,
+    /// `4`, a BinHexed Macintosh file.
+    BinHex,
+    /// `5`, a DOS binary archive.
+    Dos,
+    /// `6`, a UNIX uuencoded file.
+    UuEncoded,
+    /// `7`, a full-text search server.
+    Search,
+    /// `8`, a telnet session.
+    Telnet,
+    /// `9`, a binary file.
+    Binary,
+    /// `+`, a redundant mirror of the previous item's server.
+    Mirror,
+    /// `g`, a GIF image.
+    Gif,
+    /// `I`, an image of unspecified format.
+    Image,
+    /// `T`, a tn3270 session.
+    Tn3270,
+    /// `d`, a document such as a PDF.
+    Document,
+    /// `h`, an HTML file.
+    Html,
+    /// `i`, an informational line that doesn't link anywhere.
+    Info,
+    /// `s`, a sound file.
+    Sound,
+    /// Any item type not listed above, holding the raw type character.
+    Unknown(char),
+}
+
+impl ItemType {
+    /// Returns the character used to represent this item type in a menu.
+    #[must_use]
+    pub fn to_char(&self) -> char {
+        match self {
+            ItemType::TextFile => '0',
+            ItemType::Directory => '1',
+            ItemType::CsoPhoneBook => '2',
+            ItemType::Error => '3',
+            ItemType::BinHex => '4',
+            ItemType::Dos => '5',
+            ItemType::UuEncoded => '6',
+            ItemType::Search => '7',
+            ItemType::Telnet => '8',
+            ItemType::Binary => '9',
+            ItemType::Mirror => '+',
+            ItemType::Gif => 'g',
+            ItemType::Image => 'I',
+            ItemType::Tn3270 => 'T',
+            ItemType::Document => 'd',
+            ItemType::Html => 'h',
+            ItemType::Info => 'i',
+            ItemType::Sound => 's',
+            ItemType::Unknown(c) => *c,
+        }
+    }
+}
+
+impl From<char> for ItemType {
+    fn from(c: char) -> Self {
+        match c {
+            '0' => ItemType::TextFile,
+            '1' => ItemType::Directory,
+            '2' => ItemType::CsoPhoneBook,
+            '3' => ItemType::Error,
+            '4' => ItemType::BinHex,
+            '5' => ItemType::Dos,
+            '6' => ItemType::UuEncoded,
+            '7' => ItemType::Search,
+            '8' => ItemType::Telnet,
+            '9' => ItemType::Binary,
+            '+' => ItemType::Mirror,
+            'g' => ItemType::Gif,
+            'I' => ItemType::Image,
+            'T' => ItemType::Tn3270,
+            'd' => ItemType::Document,
+            'h' => ItemType::Html,
+            'i' => ItemType::Info,
+            's' => ItemType::Sound,
+            c => ItemType::Unknown(c),
+        }
+    }
+}
+
+impl std::fmt::Display for ItemType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_char())
+    }
+}
+
+impl GopherItem {
+    /// Returns whether the item is a search server, which needs a query from the user to be
+    /// requested with [`super::Client::search_item`].
+    #[must_use]
+    pub fn is_search(&self) -> bool {
+        self.item_type == ItemType::Search
+    }
+
+    /// Creates a [`GopherItem`] from a `gopher://host[:port]/<type><selector>` URL.
+    ///
+    /// A URL without a path refers to the server's root menu. The selector is percent-decoded and
+    /// the display string is left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gopher::{GopherItem, ItemType};
+    ///
+    /// let item = GopherItem::from_url("gopher://gopher.floodgap.com/0/gopher/proxy").unwrap();
+    /// assert_eq!(item.item_type, ItemType::TextFile);
+    /// assert_eq!(item.selector, "/gopher/proxy");
+    /// assert_eq!(item.port, 70);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if the URL is malformed, doesn't use the `gopher`
+    /// scheme, or has no host.
+    pub fn from_url(url: &str) -> Result<GopherItem, Error> {
+        let url_parsed = url::Url::parse(url)?;
+        if url_parsed.scheme() != "gopher" {
+            return Err(Error::UrlScheme(url.to_string()));
+        }
+        let host_str = url_parsed
+            .host_str()
+            .ok_or_else(|| Error::UrlNoHost(url.to_string()))?;
+        let host = net::ascii_host(host_str)?
+            .to_string();
+        let port = url_parsed.port().unwrap_or(DEFAULT_PORT);
+
+        let path = percent_decode_str(url_parsed.path().trim_start_matches('/'))
+            .decode_utf8_lossy()
+            .to_string();
+        let mut chars = path.chars();
+        let (item_type, selector) = match chars.next() {
+            Some(c) => (ItemType::from(c), chars.as_str().to_string()),
+            None => (ItemType::Directory, String::new()),
+        };
+
+        Ok(GopherItem {
+            item_type,
+            display: String::new(),
+            selector,
+            host,
+            port,
+        })
+    }
+}
+
+impl<'a> Menu {
+    /// Creates a new [`Menu`] from the given string.
+    ///
+    /// Each line must be in the form `<type><display>\t<selector>\t<host>\t<port>`, any
+    /// additional tab separated fields are ignored. Parsing stops at a line holding a single `.`,
+    /// and empty lines are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gopher::{ItemType, Menu};
+    ///
+    /// let example_menu = "iWelcome!\t\terror.host\t1\r\n\
+    ///                     1Phlog\t/phlog\texample.org\t70\r\n\
+    ///                     .\r\n";
+    /// let menu = Menu::new(example_menu).expect("Failed to parse gopher menu");
+    /// assert_eq!(menu.items[0].item_type, ItemType::Info);
+    /// assert_eq!(menu.items[1].selector, "/phlog");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::MenuFormat`] if a line is missing fields or has an invalid port.
+    pub fn new(input: &'a str) -> Result<Menu, Error> {
+        let mut items = Vec::new();
+
+        for (index, line) in input.lines().enumerate() {
+            if line == "." {
+                break;
+            }
+            let mut chars = line.chars();
+            let item_type = match chars.next() {
+                Some(c) => ItemType::from(c),
+                None => continue,
+            };
+
+            let mut fields = chars.as_str().split('\t');
+            let (display, selector, host, port) =
+                match (fields.next(), fields.next(), fields.next(), fields.next()) {
+                    (Some(display), Some(selector), Some(host), Some(port)) => {
+                        (display, selector, host, port)
+                    }
+                    _ => {
+                        return Err(Error::MenuFormat(format!(
+                            "Menu items must have a display string, selector, host, and port \
+                            separated by tabs. Line #{}: {}",
+                            index + 1,
+                            line
+                        )))
+                    }
+                };
+            let port = port.trim().parse().map_err(|_| {
+                Error::MenuFormat(format!(
+                    "Invalid port \"{}\". Line #{}: {}",
+                    port,
+                    index + 1,
+                    line
+                ))
+            })?;
+
+            items.push(GopherItem {
+                item_type,
+                display: display.to_string(),
+                selector: selector.to_string(),
+                host: host.to_string(),
+                port,
+            });
+        }
+
+        Ok(Menu { items })
+    }
+}
@@ -0,0 +1,108 @@
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+
+use super::menu::{GopherItem, ItemType, Menu};
+use super::DEFAULT_PORT;
+use crate::gemini::gemtext::{Element, Gemtext};
+
+/// The characters that must be percent-encoded in a selector to put it in a URL's path.
+const SELECTOR: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
+impl GopherItem {
+    /// Returns the URL the item links to, or `None` for items that don't link anywhere, info
+    /// lines and errors.
+    ///
+    /// Most items become `gopher://` URLs, telnet sessions become `telnet://` URLs, and HTML
+    /// items with a `URL:` selector link to the URL in the selector, as is conventional.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gopher::GopherItem;
+    ///
+    /// let item = GopherItem::from_url("gopher://example.org/0/notes.txt").unwrap();
+    /// assert_eq!(item.url().unwrap(), "gopher://example.org/0/notes.txt");
+    /// ```
+    #[must_use]
+    pub fn url(&self) -> Option<String> {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+
+        match self.item_type {
+            ItemType::Info | ItemType::Error => None,
+            ItemType::Html if self.selector.starts_with("URL:") => {
+                Some(self.selector["URL:".len()..].to_string())
+            }
+            ItemType::Telnet | ItemType::Tn3270 => Some(format!("telnet://{}:{}", host, self.port)),
+            item_type => {
+                let port = if self.port == DEFAULT_PORT {
+                    String::new()
+                } else {
+                    format!(":{}", self.port)
+                };
+                Some(format!(
+                    "gopher://{}{}/{}{}",
+                    host,
+                    port,
+                    item_type,
+                    utf8_percent_encode(&self.selector, SELECTOR)
+                ))
+            }
+        }
+    }
+}
+
+impl Menu {
+    /// Converts the menu to a gemtext document, so it can be displayed along with gemini pages.
+    ///
+    /// Items that link somewhere become links, see [`GopherItem::url`], and info lines and
+    /// errors become text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::gemini::gemtext::Element;
+    /// use leda::gopher::Menu;
+    ///
+    /// let menu = Menu::new("iWelcome!\t\terror.host\t1\r\n1Phlog\t/phlog\texample.org\t70\r\n").unwrap();
+    /// let doc = menu.to_gemtext();
+    /// assert_eq!(doc.elements[0], Element::Text(String::from("Welcome!")));
+    /// assert_eq!(
+    ///     doc.elements[1],
+    ///     Element::Link(String::from("gopher://example.org/1/phlog"), Some(String::from("Phlog")))
+    /// );
+    /// ```
+    #[must_use]
+    pub fn to_gemtext(&self) -> Gemtext {
+        let elements = self
+            .items
+            .iter()
+            .map(|item| match item.url() {
+                Some(url) => Element::Link(url, Some(item.display.clone())),
+                None if item.display.trim().is_empty() => Element::Blank,
+                None => Element::Text(item.display.clone()),
+            })
+            .collect();
+
+        Gemtext { elements }
+    }
+
+    /// Renders the menu as an HTML fragment, the same way its gemtext would be rendered, see
+    /// [`Menu::to_gemtext`] and [`Gemtext::write_html`].
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.to_gemtext().to_html()
+    }
+}
@@ -0,0 +1,260 @@
+//! Connection logic shared between the protocol clients.
+
+use std::io::{Read, Write};
+use std::net::{IpAddr, SocketAddr, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Converts the host of a URL, as returned by [`url::Url::host_str`], to the form used to connect
+/// to it.
+///
+/// `url` only converts internationalized domain names to punycode for special schemes such as
+/// `http`, for every other scheme the host is left percent-encoded. This decodes the host and
+/// runs it through IDNA so `gemini://café.example/` connects to `xn--caf-dma.example`.
+pub(crate) fn ascii_host(host_str: &str) -> Result<url::Host, url::ParseError> {
+    let decoded = percent_encoding::percent_decode_str(host_str).decode_utf8_lossy();
+    url::Host::parse(&decoded)
+}
+
+/// Returns the name a server is expected to identify itself as in a TLS handshake. This is the
+/// host itself, except that IPv6 addresses aren't wrapped in brackets.
+pub(crate) fn server_name(host: &url::Host) -> String {
+    match host {
+        url::Host::Domain(domain) => domain.clone(),
+        url::Host::Ipv4(address) => address.to_string(),
+        url::Host::Ipv6(address) => address.to_string(),
+    }
+}
+
+/// Returns `host` in the `<host>:<port>` form used to connect to it, adding `port` if `host`
+/// doesn't already name one.
+///
+/// A port is only taken to be named after the closing bracket of an IPv6 address, as in
+/// `[::1]:79`, or after the one colon of any other host. A bare IPv6 address such as `::1` has
+/// several colons and no port, so it's wrapped in brackets before the port is added.
+pub(crate) fn with_port(host: &str, port: u16) -> String {
+    if let Some(rest) = host.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((_address, "")) => format!("{}:{}", host, port),
+            _ => host.to_string(),
+        };
+    }
+
+    match host.matches(':').count() {
+        0 => format!("{}:{}", host, port),
+        1 => host.to_string(),
+        _ => format!("[{}]:{}", host, port),
+    }
+}
+
+/// Represents the ways establishing a TCP connection can fail.
+pub(crate) enum ConnectError {
+    /// The host resolved, but to no addresses.
+    NoAddress,
+    /// Resolving the host or connecting to it failed.
+    Io(std::io::Error),
+}
+
+/// How long to wait on a connection attempt before also trying the next address, as recommended
+/// by RFC 8305.
+const ATTEMPT_DELAY: Duration = Duration::from_millis(250);
+
+/// Connects to `host`, which must be in the form `<host>:<port>`.
+///
+/// When the host resolves to several addresses they're raced happy eyeballs style, alternating
+/// between IPv6 and IPv4 and starting a new attempt every 250ms until one succeeds, so an
+/// unreachable address doesn't hold up the rest. If `timeout` is `Some` then each connection
+/// attempt will give up after that duration.
+pub(crate) fn connect(host: &str, timeout: Option<Duration>) -> Result<TcpStream, ConnectError> {
+    connect_any(interleave(resolve(host)?), timeout)
+}
+
+/// Resolves `host`, which must be in the form `<host>:<port>`, to the addresses it can be reached
+/// at, in the order the resolver prefers them.
+pub(crate) fn resolve(host: &str) -> Result<Vec<SocketAddr>, ConnectError> {
+    Ok(host.to_socket_addrs().map_err(ConnectError::Io)?.collect())
+}
+
+/// Races connections to `addresses` in order, see [`connect`].
+pub(crate) fn connect_any(
+    addresses: Vec<SocketAddr>,
+    timeout: Option<Duration>,
+) -> Result<TcpStream, ConnectError> {
+    let attempt = move |address: SocketAddr| match timeout {
+        Some(timeout) => TcpStream::connect_timeout(&address, timeout),
+        None => TcpStream::connect(address),
+    };
+
+    match addresses.as_slice() {
+        [] => return Err(ConnectError::NoAddress),
+        [address] => return attempt(*address).map_err(ConnectError::Io),
+        _ => {}
+    }
+
+    let (sender, receiver) = mpsc::channel();
+    let mut addresses = addresses.into_iter().peekable();
+    let mut pending = 0;
+    let mut last_error = None;
+
+    loop {
+        if let Some(address) = addresses.next() {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                // If another attempt already won, nobody is listening and this stream is dropped.
+                let _ = sender.send(attempt(address));
+            });
+            pending += 1;
+        }
+
+        let result = if addresses.peek().is_some() {
+            match receiver.recv_timeout(ATTEMPT_DELAY) {
+                Ok(result) => result,
+                // Nothing has connected yet, so start on the next address.
+                Err(_) => continue,
+            }
+        } else {
+            match receiver.recv() {
+                Ok(result) => result,
+                Err(_) => break,
+            }
+        };
+
+        match result {
+            Ok(stream) => return Ok(stream),
+            Err(e) => {
+                pending -= 1;
+                last_error = Some(e);
+                if pending == 0 && addresses.peek().is_none() {
+                    break;
+                }
+            }
+        }
+    }
+
+    Err(last_error.map_or(ConnectError::NoAddress, ConnectError::Io))
+}
+
+/// Reorders `addresses` to alternate between address families, starting with the family of the
+/// first address, as the resolver's preferred family is usually the right one to try first.
+pub(crate) fn interleave(addresses: Vec<SocketAddr>) -> Vec<SocketAddr> {
+    let Some(first) = addresses.first() else {
+        return addresses;
+    };
+    let (mut preferred, mut other): (Vec<_>, Vec<_>) = addresses
+        .iter()
+        .partition(|address| address.is_ipv6() == first.is_ipv6());
+
+    let mut interleaved = Vec::with_capacity(addresses.len());
+    preferred.reverse();
+    other.reverse();
+    while let Some(address) = preferred.pop() {
+        interleaved.push(address);
+        if let Some(address) = other.pop() {
+            interleaved.push(address);
+        }
+    }
+    interleaved.extend(other.into_iter().rev());
+
+    interleaved
+}
+
+/// Connects to `host`, which must be in the form `<host>:<port>`, through the SOCKS5 proxy at
+/// `proxy`.
+///
+/// Host names are sent to the proxy unresolved so that name resolution happens on the proxy's
+/// side, as it must when proxying through Tor. If `timeout` is `Some` then connecting to the
+/// proxy and each step of the handshake will give up after that duration.
+pub(crate) fn connect_socks5(
+    proxy: SocketAddr,
+    host: &str,
+    timeout: Option<Duration>,
+) -> Result<TcpStream, ConnectError> {
+    let proxy_error = |message: String| {
+        ConnectError::Io(std::io::Error::other(format!(
+            "SOCKS5 proxy {}: {}",
+            proxy, message
+        )))
+    };
+
+    let (name, port) = host
+        .rsplit_once(':')
+        .and_then(|(name, port)| Some((name, port.parse::<u16>().ok()?)))
+        .ok_or_else(|| proxy_error(format!("{} isn't in the form <host>:<port>", host)))?;
+
+    // The CONNECT request's address, either an IP address or a host name for the proxy to resolve.
+    let mut request = vec![0x05, 0x01, 0x00];
+    match name.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(IpAddr::V4(address)) => {
+            request.push(0x01);
+            request.extend_from_slice(&address.octets());
+        }
+        Ok(IpAddr::V6(address)) => {
+            request.push(0x04);
+            request.extend_from_slice(&address.octets());
+        }
+        Err(_) => {
+            let length = u8::try_from(name.len())
+                .map_err(|_| proxy_error(format!("host name {} is too long", name)))?;
+            request.push(0x03);
+            request.push(length);
+            request.extend_from_slice(name.as_bytes());
+        }
+    }
+    request.extend_from_slice(&port.to_be_bytes());
+
+    let mut stream = match timeout {
+        Some(timeout) => TcpStream::connect_timeout(&proxy, timeout),
+        None => TcpStream::connect(proxy),
+    }
+    .map_err(ConnectError::Io)?;
+    stream.set_read_timeout(timeout).map_err(ConnectError::Io)?;
+    stream.set_write_timeout(timeout).map_err(ConnectError::Io)?;
+
+    // Offer only the "no authentication" method.
+    stream.write_all(&[0x05, 0x01, 0x00]).map_err(ConnectError::Io)?;
+    let mut reply = [0; 2];
+    stream.read_exact(&mut reply).map_err(ConnectError::Io)?;
+    if reply != [0x05, 0x00] {
+        return Err(proxy_error(String::from(
+            "the proxy requires authentication, which isn't supported",
+        )));
+    }
+
+    stream.write_all(&request).map_err(ConnectError::Io)?;
+    let mut reply = [0; 4];
+    stream.read_exact(&mut reply).map_err(ConnectError::Io)?;
+    if reply[1] != 0x00 {
+        let reason = match reply[1] {
+            0x01 => "general failure",
+            0x02 => "connection not allowed by ruleset",
+            0x03 => "network unreachable",
+            0x04 => "host unreachable",
+            0x05 => "connection refused",
+            0x06 => "TTL expired",
+            0x07 => "command not supported",
+            0x08 => "address type not supported",
+            _ => "unknown error",
+        };
+        return Err(proxy_error(format!("couldn't connect to {}, {}", host, reason)));
+    }
+
+    // The reply ends with the address the proxy bound to, which we have no use for.
+    let bound_length = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => {
+            let mut length = [0; 1];
+            stream.read_exact(&mut length).map_err(ConnectError::Io)?;
+            usize::from(length[0])
+        }
+        _ => return Err(proxy_error(String::from("the proxy sent an invalid reply"))),
+    };
+    let mut bound = vec![0; bound_length + 2];
+    stream.read_exact(&mut bound).map_err(ConnectError::Io)?;
+
+    stream.set_read_timeout(None).map_err(ConnectError::Io)?;
+    stream.set_write_timeout(None).map_err(ConnectError::Io)?;
+
+    Ok(stream)
+}
@@ -0,0 +1,186 @@
+//! A module with all the nex protocol functionality.
+//!
+//! Nex is about as small as a protocol gets, a client connects, sends a selector and a newline,
+//! and reads until the server closes the connection. There's no header, directories are plain
+//! text listings and everything else is sent as is.
+
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::net::{self, ConnectError};
+
+use percent_encoding::percent_decode_str;
+use thiserror::Error;
+
+/// The port nex servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 1900;
+
+/// Represents the different error types this module returns
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Failed to parse URL: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("The given URL didn't use the nex scheme: {0}")]
+    UrlScheme(String),
+    #[error("The given URL didn't have a host: {0}")]
+    UrlNoHost(String),
+    #[error("The URL couldn't be resolved to an address: {0}")]
+    UrlNoAddress(String),
+    #[error("Couldn't connect to address {1}, TCP connection error: {0}")]
+    TCPConnect(std::io::Error, String),
+    #[error("Stream IO failure, {0}: {1}")]
+    StreamIO(&'static str, std::io::Error),
+}
+
+/// Represents a client which will make nex requests.
+pub struct Client {
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a client that can be used to make nex requests
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::nex::Client;
+    ///
+    /// let client = Client::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Client {
+        Self::with_timeout(None)
+    }
+
+    /// Creates a client that can be used to make nex requests with a timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::nex::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::with_timeout(Some(Duration::new(5, 0)));
+    /// ```
+    #[must_use]
+    pub fn with_timeout(timeout: Option<Duration>) -> Client {
+        Client { timeout }
+    }
+
+    /// Sets the timeout for the client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::nex::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new();
+    /// // A timeout of 5 seconds
+    /// client.set_timeout(Some(Duration::from_secs(5)));
+    /// // No timeout
+    /// client.set_timeout(None);
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Parses a `nex://host[:port]/selector` URL into the host string to connect to and the
+    /// selector to request. The selector is the URL's decoded path without its leading `/`, and
+    /// is empty for the server's root.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::nex::Client;
+    ///
+    /// let (host, selector) = Client::parse_url("nex://example.org/notes/first%20post.txt").unwrap();
+    /// assert_eq!(host, "example.org:1900");
+    /// assert_eq!(selector, "notes/first post.txt");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if the URL is malformed, doesn't use the `nex` scheme,
+    /// or has no host.
+    pub fn parse_url(url: &str) -> Result<(String, String), Error> {
+        let url_parsed = url::Url::parse(url)?;
+        if url_parsed.scheme() != "nex" {
+            return Err(Error::UrlScheme(url.to_string()));
+        }
+        let host_str = url_parsed
+            .host_str()
+            .ok_or_else(|| Error::UrlNoHost(url.to_string()))?;
+        let host = net::ascii_host(host_str)?;
+        let port = url_parsed.port().unwrap_or(DEFAULT_PORT);
+        let selector = percent_decode_str(url_parsed.path().trim_start_matches('/'))
+            .decode_utf8_lossy()
+            .to_string();
+
+        Ok((format!("{}:{}", host, port), selector))
+    }
+
+    /// Requests `selector` from `host` and returns everything the server sent back.
+    ///
+    /// `host` may include a port in the form `<host>:<port>`, or `[<address>]:<port>` for an IPv6
+    /// address, otherwise the default nex port is used. An empty `selector` asks for the server's
+    /// root directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::nex::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request("nightfall.city", "nex/");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server.
+    pub fn request(&self, host: &str, selector: &str) -> Result<Vec<u8>, Error> {
+        let host = net::with_port(host, DEFAULT_PORT);
+
+        let mut stream = net::connect(&host, self.timeout).map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(host.clone()),
+            ConnectError::Io(e) => Error::TCPConnect(e, host.clone()),
+        })?;
+
+        stream
+            .write_all(format!("{}\n", selector).as_bytes())
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+
+        Ok(response)
+    }
+
+    /// Requests the selector on the host described by a `nex://` URL.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::nex::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request_url("nex://nightfall.city/nex/");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url or
+    /// communicating with the server.
+    pub fn request_url(&self, url: &str) -> Result<Vec<u8>, Error> {
+        let (host, selector) = Self::parse_url(url)?;
+        self.request(&host, &selector)
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,278 @@
+//! A module with all the spartan protocol functionality.
+//!
+//! Spartan is a simpler cousin of gemini that runs over plain TCP. A request names the host, the
+//! path, and the length of any data uploaded after it, and the response starts with a one digit
+//! status and a meta line just like gemini's.
+
+use std::io::{Read, Write};
+use std::time::Duration;
+
+use crate::gemini::Gemtext;
+use crate::net::{self, ConnectError};
+
+use percent_encoding::percent_decode_str;
+use thiserror::Error;
+
+/// The port spartan servers listen on unless told otherwise.
+pub const DEFAULT_PORT: u16 = 300;
+
+/// Represents the different error types this module returns
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Header is malformed: {0}")]
+    HeaderFormat(String),
+    #[error("Failed to parse URL: {0}")]
+    UrlParse(#[from] url::ParseError),
+    #[error("The given URL didn't use the spartan scheme: {0}")]
+    UrlScheme(String),
+    #[error("The given URL didn't have a host: {0}")]
+    UrlNoHost(String),
+    #[error("The URL couldn't be resolved to an address: {0}")]
+    UrlNoAddress(String),
+    #[error("Couldn't connect to address {1}, TCP connection error: {0}")]
+    TCPConnect(std::io::Error, String),
+    #[error("Stream IO failure, {0}: {1}")]
+    StreamIO(&'static str, std::io::Error),
+}
+
+/// Represents the status of a spartan response.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// `2`, the meta is the MIME type of the body.
+    Success,
+    /// `3`, the meta is the absolute path to request instead, on the same host.
+    Redirect,
+    /// `4`, the request was at fault, the meta describes why.
+    ClientError,
+    /// `5`, the server was at fault, the meta describes why.
+    ServerError,
+}
+
+/// Represents a spartan response.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// The status of the response.
+    pub status: Status,
+    /// The meta information sent with the status, its meaning depends on the status.
+    pub meta: String,
+    /// The response body. This is only `Some` for successful responses.
+    pub body: Option<Vec<u8>>,
+}
+
+impl Response {
+    /// Parses a whole response as read from a server.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::spartan::{Response, Status};
+    ///
+    /// let response = Response::parse(b"2 text/gemini\r\n# Hi\n").unwrap();
+    /// assert_eq!(response.status, Status::Success);
+    /// assert_eq!(response.meta, "text/gemini");
+    /// assert_eq!(response.body.unwrap(), b"# Hi\n");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error::HeaderFormat`] if the response doesn't start with a valid header.
+    pub fn parse(response: &[u8]) -> Result<Response, Error> {
+        let header_end = response
+            .windows(2)
+            .position(|window| window == b"\r\n")
+            .ok_or_else(|| {
+                Error::HeaderFormat(String::from(
+                    "There must be a <CR><LF> at the end of the header, but none was found.",
+                ))
+            })?;
+        let header = String::from_utf8_lossy(&response[..header_end]);
+        let body = &response[header_end + 2..];
+
+        let (status, meta) = header.split_once(' ').unwrap_or((&header, ""));
+        let status = match status {
+            "2" => Status::Success,
+            "3" => Status::Redirect,
+            "4" => Status::ClientError,
+            "5" => Status::ServerError,
+            _ => {
+                return Err(Error::HeaderFormat(format!(
+                    "The status must be a single digit from 2 to 5, got {}",
+                    status
+                )))
+            }
+        };
+
+        Ok(Response {
+            status,
+            meta: meta.to_string(),
+            body: if status == Status::Success {
+                Some(body.to_vec())
+            } else {
+                None
+            },
+        })
+    }
+
+    /// Parses the body as a [`Gemtext`] document if the response was successful and the body is
+    /// `text/gemini`. Malformed lines are kept as text, as with [`Gemtext::parse_lenient`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::spartan::Response;
+    ///
+    /// let response = Response::parse(b"2 text/gemini\r\n=> /next Next\n").unwrap();
+    /// assert_eq!(response.gemtext().unwrap().link_count(), 1);
+    /// ```
+    #[must_use]
+    pub fn gemtext(&self) -> Option<Gemtext> {
+        if !self.meta.starts_with("text/gemini") {
+            return None;
+        }
+        let body = self.body.as_ref()?;
+
+        Some(Gemtext::parse_lenient(&String::from_utf8_lossy(body)).0)
+    }
+}
+
+/// Represents a client which will make spartan requests.
+pub struct Client {
+    timeout: Option<Duration>,
+}
+
+impl Client {
+    /// Creates a client that can be used to make spartan requests
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::spartan::Client;
+    ///
+    /// let client = Client::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Client {
+        Self::with_timeout(None)
+    }
+
+    /// Creates a client that can be used to make spartan requests with a timeout
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use leda::spartan::Client;
+    /// use std::time::Duration;
+    ///
+    /// let client = Client::with_timeout(Some(Duration::new(5, 0)));
+    /// ```
+    #[must_use]
+    pub fn with_timeout(timeout: Option<Duration>) -> Client {
+        Client { timeout }
+    }
+
+    /// Sets the timeout for the client.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use leda::spartan::Client;
+    /// use std::time::Duration;
+    ///
+    /// let mut client = Client::new();
+    /// // A timeout of 5 seconds
+    /// client.set_timeout(Some(Duration::from_secs(5)));
+    /// // No timeout
+    /// client.set_timeout(None);
+    /// ```
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Sends a request for `path` to `host` on `port`, uploading `data` after it if there is
+    /// any, and parses the response.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::spartan::{self, Client};
+    ///
+    /// let client = Client::new();
+    /// let response = client.request("spartan.mozz.us", spartan::DEFAULT_PORT, "/", None)
+    ///     .expect("Failed to retrieve spartan page");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem communicating with the server,
+    /// or an [`Error::HeaderFormat`] if the response's header is malformed.
+    pub fn request(
+        &self,
+        host: &str,
+        port: u16,
+        path: &str,
+        data: Option<&[u8]>,
+    ) -> Result<Response, Error> {
+        let address = format!("{}:{}", host, port);
+        let data = data.unwrap_or_default();
+        let path = if path.is_empty() { "/" } else { path };
+
+        let mut stream = net::connect(&address, self.timeout).map_err(|e| match e {
+            ConnectError::NoAddress => Error::UrlNoAddress(address.clone()),
+            ConnectError::Io(e) => Error::TCPConnect(e, address.clone()),
+        })?;
+
+        // The host is sent without its port, IPv6 addresses included.
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let mut request = format!("{} {} {}\r\n", host, path, data.len()).into_bytes();
+        request.extend_from_slice(data);
+        stream
+            .write_all(&request)
+            .map_err(|e| Error::StreamIO("Failed to send request to server", e))?;
+
+        let mut response = Vec::new();
+        stream
+            .read_to_end(&mut response)
+            .map_err(|e| Error::StreamIO("Failed to read response from server", e))?;
+
+        Response::parse(&response)
+    }
+
+    /// Requests the page a `spartan://` URL points to. The URL's query, if it has one, is
+    /// decoded and uploaded as the request's data.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use leda::spartan::Client;
+    ///
+    /// let client = Client::new();
+    /// let response = client.request_url("spartan://spartan.mozz.us/");
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return an [`Error`](enum@Error) if there was a problem with parsing the url or
+    /// communicating with the server.
+    pub fn request_url(&self, url: &str) -> Result<Response, Error> {
+        let url_parsed = url::Url::parse(url)?;
+        if url_parsed.scheme() != "spartan" {
+            return Err(Error::UrlScheme(url.to_string()));
+        }
+        let host_str = url_parsed
+            .host_str()
+            .ok_or_else(|| Error::UrlNoHost(url.to_string()))?;
+        let host = net::ascii_host(host_str)?;
+        let port = url_parsed.port().unwrap_or(DEFAULT_PORT);
+        let data: Option<Vec<u8>> = url_parsed
+            .query()
+            .map(|query| percent_decode_str(query).collect());
+
+        self.request(&host.to_string(), port, url_parsed.path(), data.as_deref())
+    }
+}
+
+impl Default for Client {
+    fn default() -> Self {
+        Self::new()
+    }
+}